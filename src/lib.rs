@@ -1,22 +1,53 @@
 use crate::XMLGeneratorError::{
-    FilepathError, InvalidInputError, ParseError, StringConversionError, XMLGenerationError,
+    FilepathError, IOError, InvalidInputError, ParseError, XMLGenerationError,
 };
+use fake::faker::address::raw::{CityName, CountryName, ZipCode};
+use fake::faker::impls::address::CityNameGenFn;
+use fake::faker::internet::raw::{DomainSuffix, SafeEmail};
+use fake::faker::lorem::raw::Word;
+use fake::faker::name::raw::{FirstName, LastName, Name};
+use fake::faker::phone_number::raw::PhoneNumber;
+use fake::locales::{DE_DE, EN, FR_FR, IT_IT, JA_JP, PT_BR, ZH_CN};
 use fake::{Fake, Faker};
+use rand::distr::Alphanumeric;
+use rand::distr::weighted::WeightedIndex;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use std::cmp::PartialEq;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{BufRead, Cursor, Read};
 use std::ops::Deref;
 use std::path::Path;
 use std::string::String;
 use syn::{
-    AngleBracketedGenericArguments, Field, File, GenericArgument, Item, ItemStruct, ItemType,
-    PathArguments, PathSegment, Type, TypePath,
+    AngleBracketedGenericArguments, Attribute, Expr, Field, Fields, File, GenericArgument, Item,
+    ItemEnum, ItemStruct, ItemType, LitStr, PathArguments, PathSegment, Token, Type, TypePath,
 };
 use syn::__private::ToTokens;
-use xml_builder::{XMLBuilder, XMLElement, XMLVersion};
-use xsd_parser::config::GeneratorFlags;
-use xsd_parser::pipeline::parser::resolver::FileResolver;
+use tracing::warn;
+use url::Url;
+use xsd_parser::config::{GeneratorFlags, Namespace, NamespacePrefix};
+use xsd_parser::models::data::{
+    ComplexData, ComplexDataAttribute, ComplexDataElement, ComplexDataEnum, DataTypeVariant,
+    StructMode,
+};
+use xsd_parser::models::meta::{ElementMetaVariant, ElementMode, MetaTypeVariant};
+use xsd_parser::models::schema::xs::{
+    AltTypeContent, Any, AnyAttribute, AttributeGroupType, AttributeGroupTypeContent,
+    AttributeType, BasicNamespaceListItemType, ComplexBaseType, ComplexBaseTypeContent,
+    ComplexContent, ComplexContentContent, ElementType, ElementTypeContent, ExtensionTypeContent,
+    Facet, FacetType, FormChoiceType, GroupType, GroupTypeContent, NamespaceListType, OpenContent,
+    OpenContentModeType, ProcessContentsType, RestrictionContent, RestrictionTypeContent, Schema,
+    SchemaContent, SimpleBaseType, SimpleBaseTypeContent, SimpleContent, SimpleContentContent, Use,
+};
+use xsd_parser::models::schema::{MaxOccurs, NamespaceId, QName};
+use xsd_parser::models::{Ident, IdentType};
+use xsd_parser::pipeline::parser::resolver::{FileResolver, ResolveRequest, Resolver};
+use xsd_parser::pipeline::renderer::SerdeQuickXmlTypesRenderStep;
 use xsd_parser::{
     DataTypes, Generator, Interpreter, MetaTypes, Optimizer, Parser, Renderer, Schemas,
-    TypesRenderStep,
 };
 
 #[derive(Debug)]
@@ -26,12 +57,273 @@ pub enum XMLGeneratorError {
     InvalidInputError(String),
     XMLGenerationError(String),
     StringConversionError(String),
+    IOError(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    En,
+    FrFr,
+    DeDe,
+    ItIt,
+    PtBr,
+    JaJp,
+    ZhCn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GenerationMode {
+    Valid,
+    Invalid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimezoneMode {
+    Always,
+    Never,
+    Random,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttributeMode {
+    All,
+    RequiredOnly,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnumerationStrategy {
+    Uniform,
+    WeightedFirst,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptimizerSteps {
+    pub remove_empty_enum_variants: bool,
+    pub remove_empty_enums: bool,
+    pub remove_duplicate_union_variants: bool,
+    pub remove_empty_unions: bool,
+    pub convert_dynamic_to_choice: bool,
+    pub flatten_complex_types: bool,
+    pub flatten_unions: bool,
+    pub merge_enum_unions: bool,
+    pub resolve_typedefs: bool,
+    pub remove_duplicates: bool,
+    pub merge_choice_cardinalities: bool,
+}
+
+impl Default for OptimizerSteps {
+    fn default() -> Self {
+        OptimizerSteps {
+            remove_empty_enum_variants: true,
+            remove_empty_enums: true,
+            remove_duplicate_union_variants: true,
+            remove_empty_unions: true,
+            convert_dynamic_to_choice: true,
+            flatten_complex_types: true,
+            flatten_unions: true,
+            merge_enum_unions: true,
+            resolve_typedefs: true,
+            remove_duplicates: true,
+            merge_choice_cardinalities: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueContext {
+    pub element_name: String,
+    pub type_name: String,
+    pub path: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratorConfig {
+    pub exclude: Vec<String>,
+    pub attribute_mode: AttributeMode,
+    pub max_depth: usize,
+    pub annotate: bool,
+    pub max_repeats: u64,
+    pub seed: Option<u64>,
+    pub float_special_values: bool,
+    pub xsi_type: bool,
+    pub locale: Option<Locale>,
+    pub xml_lang: bool,
+    pub name_heuristics: bool,
+    pub cdata_heuristics: bool,
+    pub max_bytes: Option<usize>,
+    pub max_total_elements: Option<usize>,
+    pub max_text_length: Option<usize>,
+    pub skip_wildcards: bool,
+    pub open_content: bool,
+    pub optional_probability: Option<f64>,
+    pub mode: GenerationMode,
+    pub pretty: bool,
+    pub sort_all_groups: bool,
+    pub indent: Option<usize>,
+    pub strict: bool,
+    pub timezone: TimezoneMode,
+    pub enumeration_strategy: EnumerationStrategy,
+    pub canonical_decimals: bool,
+    pub lowercase_hex: bool,
+    pub fraction_digits: Option<u32>,
+    pub name_transform: Option<fn(&str) -> String>,
+    pub value_pools: HashMap<String, Vec<String>>,
+    pub value_providers: Vec<fn(&ValueContext) -> Option<String>>,
+    pub namespace_prefixes: HashMap<String, String>,
+    pub choice_weights: HashMap<(String, String), f64>,
+    pub optimizer_steps: OptimizerSteps,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        GeneratorConfig {
+            exclude: vec![],
+            attribute_mode: AttributeMode::All,
+            max_depth: 8,
+            annotate: false,
+            max_repeats: 3,
+            seed: None,
+            float_special_values: false,
+            xsi_type: false,
+            locale: None,
+            xml_lang: false,
+            name_heuristics: false,
+            cdata_heuristics: false,
+            max_bytes: None,
+            max_total_elements: None,
+            max_text_length: None,
+            skip_wildcards: false,
+            open_content: false,
+            optional_probability: None,
+            mode: GenerationMode::Valid,
+            pretty: true,
+            sort_all_groups: false,
+            indent: None,
+            strict: false,
+            timezone: TimezoneMode::Random,
+            enumeration_strategy: EnumerationStrategy::Uniform,
+            canonical_decimals: false,
+            lowercase_hex: false,
+            fraction_digits: None,
+            name_transform: None,
+            value_pools: HashMap::new(),
+            value_providers: vec![],
+            namespace_prefixes: HashMap::new(),
+            choice_weights: HashMap::new(),
+            optimizer_steps: OptimizerSteps::default(),
+        }
+    }
+}
+
+impl GeneratorConfig {
+    pub fn builder() -> GeneratorConfigBuilder {
+        GeneratorConfigBuilder::default()
+    }
+
+    pub fn validate(&self) -> Result<(), XMLGeneratorError> {
+        let mut problems = vec![];
+        if let Some(probability) = self.optional_probability
+            && !(0.0..=1.0).contains(&probability)
+        {
+            problems.push(format!(
+                "optional_probability must be between 0 and 1, got {}",
+                probability
+            ));
+        }
+
+        let declaration_size = XML_DECLARATION.len() + line_size(0, self);
+        if let Some(max_bytes) = self.max_bytes
+            && max_bytes < declaration_size
+        {
+            problems.push(format!(
+                "max_bytes must be at least {} to fit the XML declaration, got {}",
+                declaration_size, max_bytes
+            ));
+        }
+
+        if self.max_total_elements == Some(0) {
+            problems.push("max_total_elements must be at least 1 for the root element".to_string());
+        }
+
+        let mut pools: Vec<&String> = self.value_pools.keys().collect();
+        pools.sort();
+        for name in pools {
+            if self.value_pools[name].is_empty() {
+                problems.push(format!("Value pool for `{}` has no values", name));
+            }
+        }
+
+        let mut branches: Vec<&(String, String)> = self.choice_weights.keys().collect();
+        branches.sort();
+        for branch in branches {
+            let weight = self.choice_weights[branch];
+            if !weight.is_finite() || weight < 0.0 {
+                problems.push(format!(
+                    "Weight of `{}` in `{}` must be a non-negative number, got {}",
+                    branch.1, branch.0, weight
+                ));
+            }
+        }
+
+        let mut namespaces: Vec<&String> = self.namespace_prefixes.keys().collect();
+        namespaces.sort();
+        for (index, namespace) in namespaces.iter().enumerate() {
+            let prefix = &self.namespace_prefixes[*namespace];
+            if prefix.is_empty() || prefix.contains(':') || prefix.starts_with("xml") {
+                problems.push(format!(
+                    "Prefix `{}` for `{}` is not a valid namespace prefix",
+                    prefix, namespace
+                ));
+            }
+
+            for other in namespaces[..index].iter() {
+                if self.namespace_prefixes[*other] == *prefix {
+                    problems.push(format!(
+                        "Prefix `{}` is used for both `{}` and `{}`",
+                        prefix, other, namespace
+                    ));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            return Ok(());
+        }
+
+        Err(InvalidInputError(format!(
+            "Invalid generator config: {}",
+            problems.join("; ")
+        )))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CoverageSummary {
+    pub particles: HashMap<(String, String), usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenerationReport {
+    pub used_fallbacks: bool,
+    pub workarounds: Vec<String>,
+    pub warnings: Vec<String>,
+    pub violation: Option<String>,
+    pub coverage: CoverageSummary,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedDocument {
+    pub root_name: String,
+    pub xml: String,
 }
 
+#[derive(Clone)]
 struct FieldType {
     name: String,
     min_occurrences: Option<u64>,
     max_occurrences: Option<u64>,
+    facets: Option<Facets>,
 }
 
 impl PartialEq for FieldType {
@@ -92,44 +384,47 @@ fn get_arguments(segment: &PathSegment) -> FieldType {
 
 fn generate_field_type(type_path: &TypePath) -> FieldType {
     let stream = &type_path.path.segments;
-    for segment in stream.iter() {
-        let seg_type = segment.ident.to_string();
-        let mut field_type = get_arguments(segment);
-
-        if seg_type == "Option" {
-            field_type.min_occurrences = Some(0);
-            field_type.max_occurrences = Some(1);
-        } else if seg_type == "Vec" {
-            field_type.min_occurrences = Some(0);
-            field_type.max_occurrences = None;
-        } else {
-            unimplemented!("Unknown type: {}", seg_type);
-        }
+    let segment = stream.iter().next();
+    if segment.is_none() {
+        panic!("No type found");
+    }
+
+    let segment = segment.unwrap();
+    let seg_type = segment.ident.to_string();
+    let mut field_type = get_arguments(segment);
 
-        return field_type;
+    if seg_type == "Option" {
+        field_type.min_occurrences = Some(0);
+        field_type.max_occurrences = Some(1);
+    } else if seg_type == "Vec" {
+        field_type.min_occurrences = Some(0);
+        field_type.max_occurrences = None;
+    } else if seg_type != "Box" {
+        unimplemented!("Unknown type: {}", seg_type);
     }
 
-    panic!("No type found");
+    field_type
 }
 
 fn find_field_type(type_path: &TypePath) -> FieldType {
     let mut name = None;
 
-    let ident = &type_path.path.get_ident();
-    if ident.is_some() {
-        name = Some(ident.unwrap().to_string());
+    if let Some(segment) = type_path.path.segments.last()
+        && segment.arguments.is_none()
+    {
+        name = Some(segment.ident.to_string());
     }
 
-    let qself = type_path.qself.clone();
-    if qself.is_some() {
-        name = Some(qself.unwrap().ty.to_token_stream().to_string());
+    if let Some(qself) = &type_path.qself {
+        name = Some(qself.ty.to_token_stream().to_string());
     }
 
-    if name.is_some() {
+    if let Some(name) = name {
         return FieldType {
-            name: name.unwrap(),
+            name,
             min_occurrences: None,
             max_occurrences: None,
+            facets: None,
         };
     }
 
@@ -160,31 +455,44 @@ fn get_field_type(field_type: &Type) -> Option<FieldType> {
 fn type_alias(item_type: &ItemType) -> String {
     let value = item_type.ty.deref();
 
-    if item_type.attrs.len() > 0 {
+    if !item_type.attrs.is_empty() {
         unimplemented!("Type attributes are not supported yet");
     }
 
     value.into_token_stream().to_string()
 }
 
+fn is_builtin_module(data_types: &DataTypes, name: &str) -> bool {
+    data_types.meta.types.modules.values().any(|module| {
+        let builtin =
+            module.namespace == Some(Namespace::XS) || module.namespace == Some(Namespace::XML);
+        builtin && module.name.as_ref().is_some_and(|x| x.to_string() == name)
+    })
+}
+
 fn render(data_types: &DataTypes) -> File {
-    let renderer = Renderer::new(data_types).with_step(TypesRenderStep);
+    let renderer = Renderer::new(data_types).with_step(SerdeQuickXmlTypesRenderStep);
 
     let module = renderer.finish();
 
-    let code = module.code.to_string();
+    let mut code = module.code.to_string();
+    for (name, submodule) in module.modules.iter() {
+        if !is_builtin_module(data_types, name) {
+            code.push_str(&submodule.code.to_string());
+        }
+    }
 
-    syn::parse_file(&*code).unwrap()
+    syn::parse_file(&code).unwrap()
 }
 
 fn get_type_alias(item: &Item) -> Option<String> {
     match item {
         Item::Const(_) => unimplemented!("Item::Const"),
-        Item::Enum(_) => unimplemented!("Item::Enum"),
+        Item::Enum(_) => None,
         Item::ExternCrate(_) => unimplemented!("Item::ExternCrate"),
         Item::Fn(_) => unimplemented!("Item::Fn"),
         Item::ForeignMod(_) => unimplemented!("Item::ForeignMod"),
-        Item::Impl(_) => unimplemented!("Item::Impl"),
+        Item::Impl(_) => None,
         Item::Macro(_) => unimplemented!("Item::Macro"),
         Item::Mod(_) => unimplemented!("Item::Mod"),
         Item::Static(_) => unimplemented!("Item::Static"),
@@ -199,16 +507,41 @@ fn get_type_alias(item: &Item) -> Option<String> {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum FieldKind {
+    Element,
+    Attribute,
+    Text,
+    Group,
+}
+
+#[derive(Clone, PartialEq)]
+struct XmlNamespace {
+    prefix: String,
+    uri: String,
+}
+
 struct FieldInfo {
     name: String,
+    xml_name: String,
+    kind: FieldKind,
     field_type: FieldType,
     attributes: Vec<String>,
+    namespace: Option<XmlNamespace>,
+    substitutes: Option<Vec<(String, FieldKind, FieldType)>>,
 }
 
 struct StructInfo {
     name: String,
     attrs: Vec<String>,
     fields: Vec<FieldInfo>,
+    base: Option<String>,
+    type_name: Option<String>,
+    element_name: Option<String>,
+    unordered: bool,
+    namespace: Option<XmlNamespace>,
+    allows_xml_lang: bool,
+    definition: Option<String>,
 }
 
 impl PartialEq for FieldInfo {
@@ -217,10 +550,26 @@ impl PartialEq for FieldInfo {
             return false;
         }
 
+        if self.xml_name != other.xml_name {
+            return false;
+        }
+
+        if self.kind != other.kind {
+            return false;
+        }
+
         if self.field_type != other.field_type {
             return false;
         }
 
+        if self.namespace != other.namespace {
+            return false;
+        }
+
+        if self.substitutes != other.substitutes {
+            return false;
+        }
+
         if self.attributes.len() != other.attributes.len() {
             return false;
         }
@@ -241,6 +590,34 @@ impl PartialEq for StructInfo {
             return false;
         }
 
+        if self.base != other.base {
+            return false;
+        }
+
+        if self.type_name != other.type_name {
+            return false;
+        }
+
+        if self.element_name != other.element_name {
+            return false;
+        }
+
+        if self.unordered != other.unordered {
+            return false;
+        }
+
+        if self.namespace != other.namespace {
+            return false;
+        }
+
+        if self.allows_xml_lang != other.allows_xml_lang {
+            return false;
+        }
+
+        if self.definition != other.definition {
+            return false;
+        }
+
         if self.attrs.len() != other.attrs.len() {
             return false;
         }
@@ -264,27 +641,77 @@ impl PartialEq for StructInfo {
     }
 }
 
-fn get_field(field: &Field) -> FieldInfo {
-    if field.ident.is_none() {
-        panic!("Unnamed fields are not supported!");
+fn get_rename(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    let mut rename = None;
+
+    for attr in attrs.iter() {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let value: LitStr = meta.value()?.parse()?;
+                rename = Some(value.value());
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<Expr>()?;
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(rename)
+}
+
+fn get_xml_name(field_name: &str, rename: Option<String>) -> (String, FieldKind) {
+    let rename = rename.unwrap_or(field_name.to_string());
+
+    if rename == "$text" {
+        return (field_name.to_string(), FieldKind::Text);
+    }
+
+    if let Some(name) = rename.strip_prefix('@') {
+        return (name.to_string(), FieldKind::Attribute);
     }
-    let ident = field.ident.as_ref().unwrap();
+
+    (rename, FieldKind::Element)
+}
+
+fn get_field(field: &Field, struct_name: &str) -> Result<FieldInfo, XMLGeneratorError> {
+    let Some(ident) = field.ident.as_ref() else {
+        return Err(InvalidInputError(format!(
+            "Unnamed fields are not supported, found in `{}`",
+            struct_name
+        )));
+    };
     let field_name = ident.to_string();
     let field_type = get_field_type(&field.ty);
+    let rename = get_rename(&field.attrs).map_err(|err| {
+        ParseError(format!(
+            "Malformed serde attribute on `{}` in `{}`: {}",
+            field_name, struct_name, err
+        ))
+    })?;
+    let (xml_name, kind) = get_xml_name(&field_name, rename);
 
     let mut attrs = vec![];
     for attr in field.attrs.iter() {
         attrs.push(attr.into_token_stream().to_string());
     }
 
-    FieldInfo {
+    Ok(FieldInfo {
         name: field_name,
+        xml_name,
+        kind,
         field_type: field_type.unwrap(),
         attributes: attrs,
-    }
+        namespace: None,
+        substitutes: None,
+    })
 }
 
-fn get_struct_info(struct_item: &ItemStruct) -> StructInfo {
+fn get_struct_info(struct_item: &ItemStruct) -> Result<StructInfo, XMLGeneratorError> {
     let name = struct_item.ident.to_token_stream().to_string();
     let mut attrs = vec![];
     for attr in &struct_item.attrs {
@@ -294,29 +721,36 @@ fn get_struct_info(struct_item: &ItemStruct) -> StructInfo {
     let field_data = struct_item.fields.iter();
     let mut fields = vec![];
     for field in field_data {
-        let field_info = get_field(field);
+        let field_info = get_field(field, &name)?;
         fields.push(field_info);
     }
 
-    StructInfo {
+    Ok(StructInfo {
         name,
         attrs,
         fields,
-    }
+        base: None,
+        type_name: None,
+        element_name: None,
+        unordered: false,
+        namespace: None,
+        allows_xml_lang: false,
+        definition: None,
+    })
 }
 
-fn get_struct(item: &Item) -> Option<StructInfo> {
-    match item {
+fn get_struct(item: &Item) -> Result<Option<StructInfo>, XMLGeneratorError> {
+    let structure = match item {
         Item::Const(_) => unimplemented!("Item::Const"),
-        Item::Enum(_) => unimplemented!("Item::Enum"),
+        Item::Enum(_) => None,
         Item::ExternCrate(_) => unimplemented!("Item::ExternCrate"),
         Item::Fn(_) => unimplemented!("Item::Fn"),
         Item::ForeignMod(_) => unimplemented!("Item::ForeignMod"),
-        Item::Impl(_) => unimplemented!("Item::Impl"),
+        Item::Impl(_) => None,
         Item::Macro(_) => unimplemented!("Item::Macro"),
         Item::Mod(_) => unimplemented!("Item::Mod"),
         Item::Static(_) => unimplemented!("Item::Static"),
-        Item::Struct(x) => Option::from(get_struct_info(x)),
+        Item::Struct(x) => Option::from(get_struct_info(x)?),
         Item::Trait(_) => unimplemented!("Item::Trait"),
         Item::TraitAlias(_) => unimplemented!("Item::TraitAlias"),
         Item::Type(_) => None,
@@ -324,283 +758,5323 @@ fn get_struct(item: &Item) -> Option<StructInfo> {
         Item::Use(_) => unimplemented!("Item::Use"),
         Item::Verbatim(_) => unimplemented!("Item::Verbatim"),
         &_ => unimplemented!("Item::Other"),
-    }
+    };
+
+    Ok(structure)
 }
 
-fn get_data(data: &File) -> (Vec<String>, Vec<StructInfo>) {
+fn get_data(data: &File) -> Result<(Vec<String>, Vec<StructInfo>), XMLGeneratorError> {
     let mut type_aliases = vec![];
     let mut structs = vec![];
     for item in &data.items {
-        let type_result = get_type_alias(&item);
-        if type_result.is_some() {
-            type_aliases.push(type_result.unwrap());
+        if let Some(type_alias) = get_type_alias(item) {
+            type_aliases.push(type_alias);
         }
 
-        let struct_result = get_struct(&item);
-        if struct_result.is_some() {
-            structs.push(struct_result.unwrap());
+        if let Some(structure) = get_struct(item)? {
+            structs.push(structure);
         }
     }
 
-    (type_aliases, structs)
+    Ok((type_aliases, structs))
 }
 
-fn get_field_struct<'a>(structs: &'a Vec<StructInfo>, field: &String) -> Option<&'a StructInfo> {
-    for structure in structs.iter() {
-        if structure.name == field.deref() {
-            return Option::from(structure);
-        }
+type Occurrences = HashMap<(String, String), (u64, Option<u64>)>;
+
+fn add_element_occurrences(
+    occurrences: &mut Occurrences,
+    struct_name: &str,
+    elements: &[ComplexDataElement],
+) {
+    for element in elements.iter() {
+        let min_occurrences = element.meta.min_occurs as u64;
+        let max_occurrences = match element.meta.max_occurs {
+            MaxOccurs::Unbounded => None,
+            MaxOccurs::Bounded(x) => Some(x as u64),
+        };
+
+        let key = (struct_name.to_string(), element.field_ident.to_string());
+        occurrences.insert(key, (min_occurrences, max_occurrences));
     }
+}
 
-    None
+fn add_attribute_occurrences(
+    occurrences: &mut Occurrences,
+    struct_name: &str,
+    attributes: &[ComplexDataAttribute],
+) {
+    for attribute in attributes.iter() {
+        let min_occurrences = match attribute.meta.use_ {
+            Use::Required => 1,
+            Use::Optional => 0,
+            Use::Prohibited => 0,
+        };
+
+        let key = (struct_name.to_string(), attribute.ident.to_string());
+        occurrences.insert(key, (min_occurrences, Some(1)));
+    }
 }
 
-fn find_root(structs: &Vec<StructInfo>) -> Result<&StructInfo, XMLGeneratorError> {
-    let mut all_fields: Vec<&String> = vec![];
-    for structure in structs.iter() {
-        for field in structure.fields.iter() {
-            if !all_fields.contains(&&field.field_type.name) {
-                all_fields.push(&field.field_type.name);
+fn add_complex_occurrences(occurrences: &mut Occurrences, complex: &ComplexData) {
+    let content_type = match complex {
+        ComplexData::Enum { content_type, .. } => content_type,
+        ComplexData::Struct {
+            type_,
+            content_type,
+        } => {
+            let struct_name = type_.base.type_ident.to_string();
+            add_attribute_occurrences(occurrences, &struct_name, &type_.attributes);
+            match &type_.mode {
+                StructMode::All { elements, .. } => {
+                    add_element_occurrences(occurrences, &struct_name, elements)
+                }
+                StructMode::Sequence { elements, .. } => {
+                    add_element_occurrences(occurrences, &struct_name, elements)
+                }
+                StructMode::Empty { .. } => {}
+                StructMode::Content { content } => {
+                    if let Some(ComplexData::Enum { .. }) = content_type.as_deref() {
+                        let max_occurrences = match content.max_occurs {
+                            MaxOccurs::Unbounded => None,
+                            MaxOccurs::Bounded(x) => Some(x as u64),
+                        };
+
+                        let key = (struct_name, "content".to_string());
+                        occurrences.insert(key, (content.min_occurs as u64, max_occurrences));
+                    }
+                }
             }
+
+            content_type
         }
+    };
+
+    if let Some(content) = content_type {
+        add_complex_occurrences(occurrences, content);
     }
-    let mut dep_structs = vec![];
-    for field in all_fields.iter() {
-        let structure = get_field_struct(&structs, field);
-        if structure.is_some() {
-            dep_structs.push(structure.unwrap());
+}
+
+fn get_occurrences(data_types: &DataTypes) -> Occurrences {
+    let mut occurrences = HashMap::new();
+    for data_type in data_types.items.values() {
+        if let DataTypeVariant::Complex(complex) = &data_type.variant {
+            add_complex_occurrences(&mut occurrences, complex);
         }
     }
 
-    let mut independent_structs = vec![];
+    occurrences
+}
 
-    for structure in structs.iter() {
-        if !dep_structs.contains(&structure) {
-            independent_structs.push(structure);
+fn apply_occurrences(structs: &mut [StructInfo], occurrences: &Occurrences) {
+    for structure in structs.iter_mut() {
+        for field in structure.fields.iter_mut() {
+            let key = (structure.name.clone(), field.name.clone());
+            if let Some((min_occurrences, max_occurrences)) = occurrences.get(&key) {
+                field.field_type.min_occurrences = Some(*min_occurrences);
+                field.field_type.max_occurrences = *max_occurrences;
+            }
         }
     }
+}
 
-    if independent_structs.is_empty() {
-        return Err(InvalidInputError("No independent structs found".to_string()));
+fn get_struct_name(data_types: &DataTypes, ident: &Ident) -> Option<String> {
+    let data_type = data_types.items.get(ident)?;
+    match &data_type.variant {
+        DataTypeVariant::Complex(ComplexData::Struct { type_, .. }) => {
+            Some(type_.base.type_ident.to_string())
+        }
+        _ => None,
     }
+}
 
-    if independent_structs.len() > 1 {
-        return Err(InvalidInputError("Multiple independent structs found!".to_string()));
-    }
+fn apply_derivations(structs: &mut [StructInfo], data_types: &DataTypes) {
+    for (ident, data_type) in data_types.items.iter() {
+        let MetaTypeVariant::ComplexType(complex) = &data_type.meta.variant else {
+            continue;
+        };
 
-    for structure in structs.iter() {
-        if independent_structs.contains(&structure) {
-            return Ok(structure);
+        let Some(base) = complex.base.as_ident() else {
+            continue;
+        };
+
+        let name = get_struct_name(data_types, ident);
+        let base_name = get_struct_name(data_types, base);
+        for structure in structs.iter_mut() {
+            if Some(&structure.name) == name.as_ref() {
+                structure.base = base_name.clone();
+                structure.type_name = Some(ident.name.to_string());
+            }
         }
     }
-
-    unreachable!();
 }
 
-fn make_fake<Output: fake::Dummy<Faker> + ToString>() -> Option<String> {
-    Option::from(Faker.fake::<Output>().to_string())
-}
-
-fn get_string(type_name: &String) -> Option<String> {
-    match type_name.as_str() {
-        "i8" => make_fake::<i8>(),
-        "u8" => make_fake::<u8>(),
-        "i16" => make_fake::<i16>(),
-        "u16" => make_fake::<u16>(),
-        "i32" => make_fake::<i32>(),
-        "u32" => make_fake::<u32>(),
-        "i64" => make_fake::<i64>(),
-        "u64" => make_fake::<u64>(),
-        "i128" => make_fake::<i128>(),
-        "u128" => make_fake::<u128>(),
-        "isize" => make_fake::<isize>(),
-        "usize" => make_fake::<usize>(),
-        "f32" => make_fake::<f32>(),
-        "f64" => make_fake::<f64>(),
-        "bool" => make_fake::<bool>(),
-        "char" => make_fake::<char>(),
-        "String" => make_fake::<String>(),
-        _ => None,
+fn apply_all_groups(structs: &mut [StructInfo], data_types: &DataTypes) {
+    for data_type in data_types.items.values() {
+        let DataTypeVariant::Complex(ComplexData::Struct { type_, .. }) = &data_type.variant else {
+            continue;
+        };
+
+        if !matches!(type_.mode, StructMode::All { .. }) {
+            continue;
+        }
+
+        let name = type_.base.type_ident.to_string();
+        for structure in structs.iter_mut() {
+            if structure.name == name {
+                structure.unordered = true;
+            }
+        }
     }
 }
 
-fn get_element(
-    field: &FieldInfo,
-    structs: &Vec<StructInfo>,
-    types: &Vec<String>,
-) -> Option<XMLElement> {
-    for structure in structs {
-        if structure.name == field.field_type.name {
-            let element = generate_element(structure, structs, types);
-            return Option::from(element);
+fn apply_group_fields(structs: &mut [StructInfo], data_types: &DataTypes) {
+    for data_type in data_types.items.values() {
+        let DataTypeVariant::Complex(ComplexData::Struct { type_, .. }) = &data_type.variant else {
+            continue;
+        };
+
+        let elements = match &type_.mode {
+            StructMode::All { elements, .. } => elements,
+            StructMode::Sequence { elements, .. } => elements,
+            _ => continue,
+        };
+
+        let name = type_.base.type_ident.to_string();
+        for element in elements.iter() {
+            if element.meta.element_mode != ElementMode::Group {
+                continue;
+            }
+
+            let field_name = element.field_ident.to_string();
+            for structure in structs.iter_mut() {
+                if structure.name != name {
+                    continue;
+                }
+
+                for field in structure.fields.iter_mut() {
+                    if field.name == field_name {
+                        field.kind = FieldKind::Group;
+                    }
+                }
+            }
         }
     }
+}
 
-    None
+fn get_xml_namespace(schemas: &Schemas, id: Option<&NamespaceId>) -> Option<XmlNamespace> {
+    let id = id?;
+    let info = schemas.get_namespace_info(id)?;
+    let uri = info.namespace.as_ref()?.to_string();
+    let prefix = match &info.prefix {
+        Some(prefix) => prefix.to_string(),
+        None => format!("ns{}", id.0),
+    };
+
+    Option::from(XmlNamespace { prefix, uri })
 }
 
-fn get_child(
-    field: &FieldInfo,
-    structs: &Vec<StructInfo>,
-    types: &Vec<String>,
-) -> Option<XMLElement> {
-    let value = get_string(&field.field_type.name);
-    if value.is_some() {
-        let mut child = XMLElement::new(&field.name);
-        child.add_text(value.unwrap()).unwrap();
-        return Option::from(child);
-    }
+fn is_form_qualified(schemas: &Schemas, id: Option<&NamespaceId>, attribute: bool) -> bool {
+    let Some(info) = id.and_then(|id| schemas.get_namespace_info(id)) else {
+        return false;
+    };
+
+    info.schemas.iter().any(|schema_id| {
+        let Some(schema) = schemas.get_schema(schema_id) else {
+            return false;
+        };
 
-    get_element(&field, structs, types)
+        let form = match attribute {
+            true => &schema.attribute_form_default,
+            false => &schema.element_form_default,
+        };
+        *form == FormChoiceType::Qualified
+    })
 }
 
-fn generate_element(
-    root: &StructInfo,
-    structs: &Vec<StructInfo>,
-    types: &Vec<String>,
-) -> XMLElement {
-    let name = root.name.clone();
-    let mut element = XMLElement::new(&*name);
+fn get_qualified_namespace(
+    schemas: &Schemas,
+    data_types: &DataTypes,
+    ident: &Ident,
+    attribute: bool,
+) -> Option<XmlNamespace> {
+    let global = data_types.meta.types.items.contains_key(ident);
+    if !global && !is_form_qualified(schemas, ident.ns.as_ref(), attribute) {
+        return None;
+    }
 
-    for field in root.fields.iter() {
-        let child = get_child(field, structs, types);
-        if child.is_some() {
-            element.add_child(child.unwrap()).unwrap();
+    get_xml_namespace(schemas, ident.ns.as_ref())
+}
+
+fn set_field_namespace(structure: &mut StructInfo, name: &str, namespace: Option<XmlNamespace>) {
+    for field in structure.fields.iter_mut() {
+        if field.name == name && field.kind != FieldKind::Group {
+            field.namespace = namespace.clone();
         }
     }
-
-    element
 }
 
-fn generate_xml_data(data_types: &DataTypes) -> Result<String, XMLGeneratorError> {
-    let data = render(data_types);
+fn add_complex_namespaces(
+    structs: &mut [StructInfo],
+    complex: &ComplexData,
+    schemas: &Schemas,
+    data_types: &DataTypes,
+) {
+    let content_type = match complex {
+        ComplexData::Enum { content_type, .. } => content_type,
+        ComplexData::Struct {
+            type_,
+            content_type,
+        } => {
+            let name = type_.base.type_ident.to_string();
+            let Some(structure) = structs.iter_mut().find(|x| x.name == name) else {
+                return;
+            };
 
-    let mut xml = XMLBuilder::new()
-        .version(XMLVersion::XML1_1)
-        .encoding("UTF-8".into())
-        .build();
+            for attribute in type_.attributes.iter() {
+                let namespace =
+                    get_qualified_namespace(schemas, data_types, &attribute.meta.ident, true);
+                set_field_namespace(structure, &attribute.ident.to_string(), namespace);
+            }
 
-    let (type_aliases, structs) = get_data(&data);
+            let elements = match &type_.mode {
+                StructMode::All { elements, .. } => &elements[..],
+                StructMode::Sequence { elements, .. } => &elements[..],
+                StructMode::Empty { .. } | StructMode::Content { .. } => &[],
+            };
 
-    let root = find_root(&structs)?;
-    let root_element = generate_element(&root, &structs, &type_aliases);
+            for element in elements.iter() {
+                let namespace =
+                    get_qualified_namespace(schemas, data_types, &element.meta.ident, false);
+                set_field_namespace(structure, &element.field_ident.to_string(), namespace);
+            }
 
-    let mut writer: Vec<u8> = Vec::new();
-    xml.set_root_element(root_element);
-    let result = xml.generate(&mut writer);
-    if result.is_err() {
-        return Err(XMLGenerationError(result.err().unwrap().to_string()));
-    }
+            content_type
+        }
+    };
 
-    let result = String::from_utf8(writer);
-    match result {
-        Ok(x) => Ok(x),
-        Err(err) => Err(StringConversionError(err.to_string())),
+    if let Some(content) = content_type {
+        add_complex_namespaces(structs, content, schemas, data_types);
     }
 }
 
-fn generate_schema(filepath: Box<Path>) -> Result<Schemas, XMLGeneratorError> {
-    let path = filepath.canonicalize();
-    if let Err(_err) = path {
-        return Err(FilepathError);
+fn apply_namespaces(structs: &mut [StructInfo], schemas: &Schemas, data_types: &DataTypes) {
+    for data_type in data_types.items.values() {
+        if let DataTypeVariant::Complex(complex) = &data_type.variant {
+            add_complex_namespaces(structs, complex, schemas, data_types);
+        }
     }
 
-    let schemas = Parser::new()
-        .with_resolver(FileResolver::new())
-        .with_default_namespaces()
-        .add_schema_from_file(path.unwrap());
+    for ident in data_types.items.keys() {
+        if ident.type_ != IdentType::Element {
+            continue;
+        }
 
-    if let Err(err) = schemas {
-        return Err(ParseError(err.to_string()));
+        let element_name = Some(ident.name.to_string());
+        for structure in structs.iter_mut() {
+            if structure.element_name == element_name && structure.namespace.is_none() {
+                structure.namespace = get_xml_namespace(schemas, ident.ns.as_ref());
+            }
+        }
     }
-
-    Ok(schemas.unwrap().finish())
 }
 
-fn generate_schema_from_string(string: &String) -> Result<Schemas, XMLGeneratorError> {
-    let schemas = Parser::new()
-        .with_resolver(FileResolver::new())
-        .with_default_namespaces()
-        .add_schema_from_str(string);
+fn get_element_struct_name(data_types: &DataTypes, ident: &Ident) -> Option<String> {
+    let mut ident = ident;
+    let mut visited = vec![];
+    while !visited.contains(&ident) {
+        visited.push(ident);
+        if let Some(name) = get_struct_name(data_types, ident) {
+            return Option::from(name);
+        }
 
-    if let Err(err) = schemas {
-        return Err(ParseError(err.to_string()));
+        let data_type = data_types.items.get(ident)?;
+        let MetaTypeVariant::Reference(reference) = &data_type.meta.variant else {
+            return None;
+        };
+
+        ident = &reference.type_;
     }
 
-    Ok(schemas.unwrap().finish())
+    None
 }
 
-fn optimise_meta_types(meta_types: MetaTypes) -> MetaTypes {
-    Optimizer::new(meta_types)
-        .remove_empty_enum_variants()
-        .remove_empty_enums()
-        .remove_duplicate_union_variants()
-        .remove_empty_unions()
-        .use_unrestricted_base_type()
-        .convert_dynamic_to_choice()
-        .flatten_complex_types()
-        .flatten_unions()
-        .merge_enum_unions()
-        .resolve_typedefs()
-        .remove_duplicates()
-        .merge_choice_cardinalities()
-        .finish()
+fn get_enum<'a>(data: &'a File, name: &str) -> Option<&'a ItemEnum> {
+    data.items.iter().find_map(|item| match item {
+        Item::Enum(x) if x.ident == name => Some(x),
+        _ => None,
+    })
 }
 
-fn generate_meta_types(schemas: &Schemas, optimise: bool) -> Result<MetaTypes, XMLGeneratorError> {
-    let meta_types = Interpreter::new(&schemas).with_buildin_types();
-    if let Err(err) = meta_types {
-        return Err(ParseError(err.to_string()));
-    }
+fn get_variant_type(item: &ItemEnum, element: &ComplexDataElement) -> Option<FieldType> {
+    let mut variants = item.variants.iter();
+    let variant = variants.find(|x| x.ident == element.variant_ident)?;
+    let Fields::Unnamed(fields) = &variant.fields else {
+        return None;
+    };
 
-    let meta_types = meta_types.unwrap().with_default_typedefs();
-    if let Err(err) = meta_types {
-        return Err(ParseError(err.to_string()));
-    }
+    get_field_type(&fields.unnamed.first()?.ty)
+}
 
-    let meta_types = meta_types.unwrap().with_xs_any_type();
-    if let Err(err) = meta_types {
-        return Err(ParseError(err.to_string()));
-    }
+fn apply_substitutes(structs: &mut [StructInfo], data: &File, data_types: &DataTypes) {
+    for data_type in data_types.items.values() {
+        let DataTypeVariant::Complex(ComplexData::Enum { type_, .. }) = &data_type.variant else {
+            continue;
+        };
 
-    let meta_types = meta_types.unwrap().finish();
+        let MetaTypeVariant::ComplexType(complex) = &data_type.meta.variant else {
+            continue;
+        };
 
-    if let Err(err) = meta_types {
-        return Err(ParseError(err.to_string()));
-    }
+        let name = type_.base.type_ident.to_string();
+        let Some(item) = complex.is_dynamic.then(|| get_enum(data, &name)).flatten() else {
+            continue;
+        };
 
-    if optimise {
-        Ok(optimise_meta_types(meta_types.unwrap()))
-    } else {
-        Ok(meta_types.unwrap())
-    }
-}
+        for structure in structs.iter_mut() {
+            for field in structure.fields.iter_mut() {
+                if field.kind != FieldKind::Element || field.field_type.name != name {
+                    continue;
+                }
 
-fn generate_data_types(meta_types: &MetaTypes) -> Result<DataTypes, XMLGeneratorError> {
-    let data_types = Generator::new(meta_types)
-        .flags(GeneratorFlags::all())
-        .generate_named_types();
+                let mut substitutes = vec![];
+                for element in type_.elements.iter() {
+                    let Some(field_type) = get_variant_type(item, element) else {
+                        continue;
+                    };
 
-    if let Err(err) = data_types {
-        return Err(ParseError(err.to_string()));
-    }
+                    let xml_name = match element.meta.ident.type_ {
+                        IdentType::Element => element.meta.ident.name.to_string(),
+                        _ => field.xml_name.clone(),
+                    };
+                    substitutes.push((xml_name, FieldKind::Element, field_type));
+                }
 
-    Ok(data_types.unwrap().finish())
+                field.substitutes = Some(substitutes);
+            }
+        }
+    }
 }
 
-pub fn generate_xml(filepath: Box<Path>) -> Result<String, XMLGeneratorError> {
-    let schemas = generate_schema(filepath)?;
+fn get_choice<'a>(complex: &'a ComplexData) -> Option<&'a ComplexDataEnum<'a>> {
+    let choice = match complex {
+        ComplexData::Enum { type_, .. } => type_,
+        ComplexData::Struct { content_type, .. } => match content_type.as_deref()? {
+            ComplexData::Enum { type_, .. } => type_,
+            ComplexData::Struct { .. } => return None,
+        },
+    };
 
-    let meta_types = generate_meta_types(&schemas, true)?;
+    (!choice.base.is_dynamic).then_some(choice)
+}
 
+fn apply_choices(structs: &mut [StructInfo], data: &File, data_types: &DataTypes) {
+    for data_type in data_types.items.values() {
+        let DataTypeVariant::Complex(complex) = &data_type.variant else {
+            continue;
+        };
+
+        let Some(choice) = get_choice(complex) else {
+            continue;
+        };
+
+        let name = choice.base.type_ident.to_string();
+        let Some(item) = get_enum(data, &name) else {
+            continue;
+        };
+
+        let mut branches = vec![];
+        for element in choice.elements.iter() {
+            let Some(field_type) = get_variant_type(item, element) else {
+                continue;
+            };
+
+            let kind = match element.meta.element_mode {
+                ElementMode::Group => FieldKind::Group,
+                ElementMode::Element => FieldKind::Element,
+            };
+            branches.push((element.meta.ident.name.to_string(), kind, field_type));
+        }
+
+        for structure in structs.iter_mut() {
+            for field in structure.fields.iter_mut() {
+                if !matches!(field.kind, FieldKind::Element | FieldKind::Group)
+                    || field.field_type.name != name
+                    || field.substitutes.is_some()
+                {
+                    continue;
+                }
+
+                field.substitutes = Some(branches.clone());
+            }
+        }
+    }
+}
+
+fn apply_element_names(structs: &mut [StructInfo], data_types: &DataTypes) {
+    for ident in data_types.items.keys() {
+        if ident.type_ != IdentType::Element {
+            continue;
+        }
+
+        let Some(name) = get_element_struct_name(data_types, ident) else {
+            continue;
+        };
+
+        for structure in structs.iter_mut() {
+            if structure.name == name && structure.element_name.is_none() {
+                structure.element_name = Some(ident.name.to_string());
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Facets {
+    min: Option<i128>,
+    max: Option<i128>,
+    min_decimal: Option<DecimalBound>,
+    max_decimal: Option<DecimalBound>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    total_digits: Option<u32>,
+    fraction_digits: Option<u32>,
+    white_space: Option<String>,
+    pattern: Option<String>,
+    enumerations: Vec<String>,
+    fixed: Option<String>,
+    explicit_timezone: Option<String>,
+    bound_timezone: Option<String>,
+    base_type: Option<String>,
+    type_name: Option<String>,
+    ignored: Vec<String>,
+}
+
+impl Facets {
+    fn is_empty(&self) -> bool {
+        self.min.is_none()
+            && self.max.is_none()
+            && self.min_decimal.is_none()
+            && self.max_decimal.is_none()
+            && self.min_length.is_none()
+            && self.max_length.is_none()
+            && self.total_digits.is_none()
+            && self.fraction_digits.is_none()
+            && self.white_space.is_none()
+            && self.pattern.is_none()
+            && self.enumerations.is_empty()
+            && self.fixed.is_none()
+            && self.explicit_timezone.is_none()
+            && self.bound_timezone.is_none()
+            && self.base_type.is_none()
+            && self.type_name.is_none()
+            && self.ignored.is_empty()
+    }
+
+    fn intersect(&self, other: &Facets) -> Facets {
+        let min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+
+        let max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        let min_decimal = match (self.min_decimal, other.min_decimal) {
+            (Some(a), Some(b)) => Some(a.tighter(b, true)),
+            (a, b) => a.or(b),
+        };
+
+        let max_decimal = match (self.max_decimal, other.max_decimal) {
+            (Some(a), Some(b)) => Some(a.tighter(b, false)),
+            (a, b) => a.or(b),
+        };
+
+        let min_length = match (self.min_length, other.min_length) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+
+        let max_length = match (self.max_length, other.max_length) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        let total_digits = match (self.total_digits, other.total_digits) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        let fraction_digits = match (self.fraction_digits, other.fraction_digits) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        let white_space = self.white_space.clone().or(other.white_space.clone());
+        let pattern = self.pattern.clone().or(other.pattern.clone());
+        let enumerations = if self.enumerations.is_empty() {
+            other.enumerations.clone()
+        } else {
+            self.enumerations.clone()
+        };
+        let fixed = self.fixed.clone().or(other.fixed.clone());
+        let explicit_timezone = self
+            .explicit_timezone
+            .clone()
+            .or(other.explicit_timezone.clone());
+        let bound_timezone = self.bound_timezone.clone().or(other.bound_timezone.clone());
+        let base_type = self.base_type.clone().or(other.base_type.clone());
+        let type_name = self.type_name.clone().or(other.type_name.clone());
+        let mut ignored = self.ignored.clone();
+        for x in other.ignored.iter() {
+            if !ignored.contains(x) {
+                ignored.push(x.clone());
+            }
+        }
+
+        Facets {
+            min,
+            max,
+            min_decimal,
+            max_decimal,
+            min_length,
+            max_length,
+            total_digits,
+            fraction_digits,
+            white_space,
+            pattern,
+            enumerations,
+            fixed,
+            explicit_timezone,
+            bound_timezone,
+            base_type,
+            type_name,
+            ignored,
+        }
+    }
+
+    fn lower_bound(&self, scale: u32) -> Option<i128> {
+        let min = self
+            .min
+            .and_then(|x| x.checked_mul(10i128.checked_pow(scale)?));
+        match (min, self.min_decimal.and_then(|x| x.lower(scale))) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    fn upper_bound(&self, scale: u32) -> Option<i128> {
+        let max = self
+            .max
+            .and_then(|x| x.checked_mul(10i128.checked_pow(scale)?));
+        match (max, self.max_decimal.and_then(|x| x.upper(scale))) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// A bound with a fractional part, held as `digits / 10^scale`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct DecimalBound {
+    digits: i128,
+    scale: u32,
+    exclusive: bool,
+}
+
+impl DecimalBound {
+    fn rescale(&self, scale: u32) -> Option<(i128, bool)> {
+        if scale >= self.scale {
+            let digits = self
+                .digits
+                .checked_mul(10i128.checked_pow(scale - self.scale)?)?;
+            return Option::from((digits, true));
+        }
+
+        let divisor = 10i128.checked_pow(self.scale - scale)?;
+        let exact = self.digits.rem_euclid(divisor) == 0;
+        Option::from((self.digits.div_euclid(divisor), exact))
+    }
+
+    fn lower(&self, scale: u32) -> Option<i128> {
+        let (floor, exact) = self.rescale(scale)?;
+        match exact && !self.exclusive {
+            true => Option::from(floor),
+            false => floor.checked_add(1),
+        }
+    }
+
+    fn upper(&self, scale: u32) -> Option<i128> {
+        let (floor, exact) = self.rescale(scale)?;
+        match exact && self.exclusive {
+            true => floor.checked_sub(1),
+            false => Option::from(floor),
+        }
+    }
+
+    fn tighter(self, other: DecimalBound, lower: bool) -> DecimalBound {
+        let scale = self.scale.max(other.scale);
+        match lower {
+            true if self.lower(scale) >= other.lower(scale) => self,
+            false if self.upper(scale) <= other.upper(scale) => self,
+            _ => other,
+        }
+    }
+}
+
+type TypeName = (Option<String>, String);
+
+struct TypeInfo {
+    base: Option<TypeName>,
+    facets: Facets,
+}
+
+type SimpleTypes = HashMap<TypeName, TypeInfo>;
+
+fn local_name(name: &QName) -> String {
+    String::from_utf8_lossy(name.local_name()).to_string()
+}
+
+fn qualified_name(name: &QName) -> String {
+    match name.prefix() {
+        Some(prefix) => format!("{}:{}", String::from_utf8_lossy(prefix), local_name(name)),
+        None => local_name(name),
+    }
+}
+
+fn get_type_name(name: &QName) -> TypeName {
+    let namespace = name.namespace().map(|x| x.to_string());
+    (namespace, local_name(name))
+}
+
+fn find_type<'a, T>(types: &'a HashMap<TypeName, T>, name: &TypeName) -> Option<&'a T> {
+    if let Some(x) = types.get(name) {
+        return Option::from(x);
+    }
+
+    if name.0.is_some() {
+        return None;
+    }
+
+    let mut matches = types.iter().filter(|(key, _)| key.1 == name.1);
+    match (matches.next(), matches.next()) {
+        (Some((_, x)), None) => Option::from(x),
+        _ => None,
+    }
+}
+
+fn parse_time(value: &str) -> Option<(i128, bool, Option<String>)> {
+    let (time, timezone) = match value.find(['Z', '+', '-']) {
+        Some(index) => (&value[..index], Some(value[index..].to_string())),
+        None => (value, None),
+    };
+
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut parts = time.split(':').map(|x| x.parse::<i128>().ok());
+    let (Some(Some(hours)), Some(Some(minutes)), Some(Some(seconds)), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+
+    if hours > 24 || minutes > 59 || seconds > 59 {
+        return None;
+    }
+
+    let fractional = fraction.chars().any(|x| x != '0');
+    Option::from(((hours * 60 + minutes) * 60 + seconds, fractional, timezone))
+}
+
+fn parse_decimal(value: &str) -> Option<(i128, u32)> {
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let fraction = fraction.trim_end_matches('0');
+    let unsigned = integer.strip_prefix(['-', '+']).unwrap_or(integer);
+    let digits = format!("{}{}", unsigned, fraction);
+    if digits.is_empty() || !digits.chars().all(|x| x.is_ascii_digit()) {
+        return None;
+    }
+
+    let digits = digits.parse::<i128>().ok()?;
+    let digits = if integer.starts_with('-') {
+        -digits
+    } else {
+        digits
+    };
+    Option::from((digits, fraction.len() as u32))
+}
+
+fn add_bound(facets: &mut Facets, facet: &FacetType, lower: bool, exclusive: bool) {
+    let bound = match parse_decimal(facet.value.trim()) {
+        Some((digits, 0)) => Option::from(digits),
+        Some((digits, scale)) => {
+            let bound = Option::from(DecimalBound {
+                digits,
+                scale,
+                exclusive,
+            });
+            match lower {
+                true => facets.min_decimal = bound,
+                false => facets.max_decimal = bound,
+            }
+            return;
+        }
+        None => parse_bound(facets, facet, lower),
+    };
+
+    if bound.is_none() {
+        let name = match (lower, exclusive) {
+            (true, false) => "minInclusive",
+            (true, true) => "minExclusive",
+            (false, false) => "maxInclusive",
+            (false, true) => "maxExclusive",
+        };
+        facets
+            .ignored
+            .push(format!("`{}` facet `{}`", name, facet.value.trim()));
+    }
+
+    match (lower, exclusive) {
+        (true, false) => facets.min = bound,
+        (true, true) => facets.min = bound.map(|x| x.saturating_add(1)),
+        (false, false) => facets.max = bound,
+        (false, true) => facets.max = bound.map(|x| x.saturating_sub(1)),
+    }
+}
+
+fn parse_bound(facets: &mut Facets, facet: &FacetType, lower: bool) -> Option<i128> {
+    let value = facet.value.trim();
+    let (seconds, fractional, timezone) = parse_time(value)?;
+    facets.bound_timezone = timezone;
+    if fractional && lower {
+        Option::from(seconds + 1)
+    } else {
+        Option::from(seconds)
+    }
+}
+
+fn unescape_value(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+
+        let entity = &rest[1..end];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|x| u32::from_str_radix(x, 16))
+                .or_else(|| entity.strip_prefix('#').map(|x| x.parse::<u32>()))
+                .and_then(|x| x.ok())
+                .and_then(char::from_u32),
+        };
+
+        match character {
+            Some(character) => {
+                unescaped.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    unescaped.push_str(rest);
+    unescaped
+}
+
+fn parse_count<T: std::str::FromStr>(facet: &FacetType) -> Option<T> {
+    facet.value.trim().parse::<T>().ok()
+}
+
+fn strip_anchors(pattern: &str) -> &str {
+    let pattern = pattern.strip_prefix('^').unwrap_or(pattern);
+    let Some(stripped) = pattern.strip_suffix('$') else {
+        return pattern;
+    };
+
+    let backslashes = stripped.len() - stripped.trim_end_matches('\\').len();
+    if backslashes % 2 == 0 {
+        stripped
+    } else {
+        pattern
+    }
+}
+
+fn translate_pattern(pattern: &str) -> String {
+    let pattern = strip_anchors(pattern);
+    let mut translated = String::new();
+    let mut in_class = false;
+    let mut escaped = false;
+    for x in pattern.chars() {
+        match x {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '^' | '$' if !in_class => translated.push('\\'),
+            _ => {}
+        }
+
+        translated.push(x);
+    }
+
+    translated
+}
+
+fn add_pattern(facets: &mut Facets, pattern: &str) {
+    let pattern = format!("(?:{})", translate_pattern(pattern));
+    facets.pattern = match facets.pattern.take() {
+        Some(x) => Option::from(format!("{}|{}", x, pattern)),
+        None => Option::from(pattern),
+    };
+}
+
+fn add_facet(facets: &mut Facets, facet: &Facet) {
+    match facet {
+        Facet::MinInclusive(x) => add_bound(facets, x, true, false),
+        Facet::MinExclusive(x) => add_bound(facets, x, true, true),
+        Facet::MaxInclusive(x) => add_bound(facets, x, false, false),
+        Facet::MaxExclusive(x) => add_bound(facets, x, false, true),
+        Facet::Length(x) => {
+            facets.min_length = parse_count(x);
+            facets.max_length = parse_count(x);
+        }
+        Facet::MinLength(x) => facets.min_length = parse_count(x),
+        Facet::MaxLength(x) => facets.max_length = parse_count(x),
+        Facet::TotalDigits(x) => facets.total_digits = parse_count(x),
+        Facet::FractionDigits(x) => facets.fraction_digits = parse_count(x),
+        Facet::WhiteSpace(x) => facets.white_space = Option::from(x.value.trim().to_string()),
+        Facet::Pattern(x) => add_pattern(facets, &unescape_value(&x.value)),
+        Facet::Enumeration(x) => facets.enumerations.push(unescape_value(&x.value)),
+        Facet::ExplicitTimezone(x) => {
+            facets.explicit_timezone = Option::from(x.value.trim().to_string())
+        }
+        Facet::Assertion(_) => facets.ignored.push("`assertion` facet".to_string()),
+    }
+}
+
+fn get_simple_restriction(simple_type: &SimpleBaseType) -> Option<TypeInfo> {
+    for content in simple_type.content.iter() {
+        if let SimpleBaseTypeContent::Restriction(restriction) = content {
+            let mut facets = Facets::default();
+            for x in restriction.content.iter() {
+                if let RestrictionContent::Facet(facet) = x {
+                    add_facet(&mut facets, facet);
+                }
+            }
+
+            let base = restriction.base.as_ref().map(get_type_name);
+            return Option::from(TypeInfo { base, facets });
+        }
+    }
+
+    None
+}
+
+fn resolve_facets(restriction: &TypeInfo, simple_types: &SimpleTypes) -> Facets {
+    let mut facets = restriction.facets.clone();
+    let mut base = restriction.base.as_ref();
+    let mut visited = vec![];
+    while let Some(name) = base {
+        if visited.contains(&name) {
+            break;
+        }
+        visited.push(name);
+
+        let Some(parent) = find_type(simple_types, name) else {
+            facets.base_type = Option::from(name.1.clone());
+            break;
+        };
+
+        facets = facets.intersect(&parent.facets);
+        base = parent.base.as_ref();
+    }
+
+    facets
+}
+
+fn get_type_restriction(type_name: &QName) -> TypeInfo {
+    TypeInfo {
+        base: Option::from(get_type_name(type_name)),
+        facets: Facets::default(),
+    }
+}
+
+fn get_simple_content_restriction(complex: &ComplexBaseType) -> Option<TypeInfo> {
+    for content in complex.content.iter() {
+        let ComplexBaseTypeContent::SimpleContent(simple_content) = content else {
+            continue;
+        };
+
+        for x in simple_content.content.iter() {
+            match x {
+                SimpleContentContent::Extension(extension) => {
+                    return Option::from(get_type_restriction(&extension.base));
+                }
+                SimpleContentContent::Restriction(restriction) => {
+                    let mut facets = Facets::default();
+                    for x in restriction.content.iter() {
+                        if let RestrictionTypeContent::Facet(facet) = x {
+                            add_facet(&mut facets, facet);
+                        }
+                    }
+
+                    let base = Option::from(get_type_name(&restriction.base));
+                    return Option::from(TypeInfo { base, facets });
+                }
+                SimpleContentContent::Annotation(_) => {}
+            }
+        }
+    }
+
+    None
+}
+
+fn get_declared_facets(
+    simple_types: &SimpleTypes,
+    restriction: Option<TypeInfo>,
+    fixed: Option<&String>,
+) -> Facets {
+    let mut facets = match restriction {
+        Some(restriction) => resolve_facets(&restriction, simple_types),
+        None => Facets::default(),
+    };
+
+    if let Some(fixed) = fixed {
+        facets.fixed = Option::from(unescape_value(fixed));
+    }
+
+    facets
+}
+
+trait SchemaVisitor {
+    fn visit_schema(&mut self, _schema: &Schema) {}
+
+    fn visit_element(&mut self, _element: &ElementType) {}
+
+    fn visit_attribute(&mut self, _attribute: &AttributeType) {}
+}
+
+fn walk_element(visitor: &mut impl SchemaVisitor, element: &ElementType) {
+    visitor.visit_element(element);
+    for content in element.content.iter() {
+        if let ElementTypeContent::ComplexType(x) = content {
+            walk_complex(visitor, x);
+        }
+    }
+}
+
+fn walk_group(visitor: &mut impl SchemaVisitor, group: &GroupType) {
+    for content in group.content.iter() {
+        match content {
+            GroupTypeContent::Element(x) => walk_element(visitor, x),
+            GroupTypeContent::Group(x)
+            | GroupTypeContent::All(x)
+            | GroupTypeContent::Choice(x)
+            | GroupTypeContent::Sequence(x) => walk_group(visitor, x),
+            _ => {}
+        }
+    }
+}
+
+fn walk_complex_content(visitor: &mut impl SchemaVisitor, complex_content: &ComplexContent) {
+    for content in complex_content.content.iter() {
+        match content {
+            ComplexContentContent::Extension(extension) => {
+                for x in extension.content.iter() {
+                    match x {
+                        ExtensionTypeContent::Group(x)
+                        | ExtensionTypeContent::All(x)
+                        | ExtensionTypeContent::Choice(x)
+                        | ExtensionTypeContent::Sequence(x) => walk_group(visitor, x),
+                        ExtensionTypeContent::Attribute(x) => visitor.visit_attribute(x),
+                        _ => {}
+                    }
+                }
+            }
+            ComplexContentContent::Restriction(restriction) => {
+                for x in restriction.content.iter() {
+                    match x {
+                        RestrictionTypeContent::Group(x)
+                        | RestrictionTypeContent::All(x)
+                        | RestrictionTypeContent::Choice(x)
+                        | RestrictionTypeContent::Sequence(x) => walk_group(visitor, x),
+                        RestrictionTypeContent::Attribute(x) => visitor.visit_attribute(x),
+                        _ => {}
+                    }
+                }
+            }
+            ComplexContentContent::Annotation(_) => {}
+        }
+    }
+}
+
+fn walk_complex(visitor: &mut impl SchemaVisitor, complex: &ComplexBaseType) {
+    for content in complex.content.iter() {
+        match content {
+            ComplexBaseTypeContent::Group(x)
+            | ComplexBaseTypeContent::All(x)
+            | ComplexBaseTypeContent::Choice(x)
+            | ComplexBaseTypeContent::Sequence(x) => walk_group(visitor, x),
+            ComplexBaseTypeContent::Attribute(x) => visitor.visit_attribute(x),
+            ComplexBaseTypeContent::ComplexContent(x) => walk_complex_content(visitor, x),
+            _ => {}
+        }
+    }
+}
+
+fn walk_schemas(visitor: &mut impl SchemaVisitor, schemas: &Schemas) {
+    for (_, schema) in schemas.schemas() {
+        visitor.visit_schema(schema);
+        for content in schema.content.iter() {
+            match content {
+                SchemaContent::Element(x) => walk_element(visitor, x),
+                SchemaContent::Attribute(x) => visitor.visit_attribute(x),
+                SchemaContent::ComplexType(x) => walk_complex(visitor, x),
+                SchemaContent::Group(x) => walk_group(visitor, x),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Definition {
+    Global(Option<String>),
+    Type(TypeName),
+    Element(TypeName),
+    Local(Box<Definition>, String),
+}
+
+fn get_definition_name(definition: &Definition) -> String {
+    match definition {
+        Definition::Global(_) => String::new(),
+        Definition::Type((_, name)) | Definition::Element((_, name)) => name.clone(),
+        Definition::Local(parent, name) => format!("{}/{}", get_definition_name(parent), name),
+    }
+}
+
+type Declarations = HashMap<(FieldKind, String), Facets>;
+type Restrictions = HashMap<Definition, Declarations>;
+type DeclaredWildcards = HashMap<Definition, Vec<Wildcard>>;
+
+#[derive(Default)]
+struct RestrictionBuilder<'a> {
+    simple_types: SimpleTypes,
+    complex_types: HashMap<TypeName, &'a ComplexBaseType>,
+    groups: HashMap<TypeName, &'a GroupType>,
+    attribute_groups: HashMap<TypeName, &'a AttributeGroupType>,
+    elements: HashMap<TypeName, &'a ElementType>,
+    attributes: HashMap<TypeName, &'a AttributeType>,
+    schema: Option<&'a Schema>,
+    expanded_types: Vec<TypeName>,
+    expanded_groups: Vec<TypeName>,
+    restrictions: Restrictions,
+    wildcards: DeclaredWildcards,
+    previous: Option<String>,
+    lang_definitions: HashSet<Definition>,
+}
+
+impl<'a> RestrictionBuilder<'a> {
+    fn insert(&mut self, definition: &Definition, key: (FieldKind, String), facets: Facets) {
+        let declarations = self.restrictions.entry(definition.clone()).or_default();
+        if !facets.is_empty() {
+            declarations.insert(key, facets);
+        }
+    }
+
+    fn get_element_facets(&self, element: &ElementType) -> Facets {
+        let mut restriction = element.type_.as_ref().map(get_type_restriction);
+        for content in element.content.iter() {
+            match content {
+                ElementTypeContent::SimpleType(x) => restriction = get_simple_restriction(x),
+                ElementTypeContent::ComplexType(x) => {
+                    restriction = get_simple_content_restriction(x)
+                }
+                _ => {}
+            }
+        }
+
+        let mut facets =
+            get_declared_facets(&self.simple_types, restriction, element.fixed.as_ref());
+        facets.type_name = element.type_.as_ref().map(qualified_name);
+        facets
+    }
+
+    fn get_attribute_facets(&self, attribute: &AttributeType) -> Facets {
+        let restriction = match &attribute.simple_type {
+            Some(x) => get_simple_restriction(x),
+            None => attribute.type_.as_ref().map(get_type_restriction),
+        };
+
+        let mut facets =
+            get_declared_facets(&self.simple_types, restriction, attribute.fixed.as_ref());
+        facets.type_name = attribute.type_.as_ref().map(qualified_name);
+        facets
+    }
+
+    fn check_form(&self, facets: &mut Facets, form: Option<&FormChoiceType>, attribute: bool) {
+        let Some(schema) = self.schema else {
+            return;
+        };
+
+        let default = match attribute {
+            true => &schema.attribute_form_default,
+            false => &schema.element_form_default,
+        };
+        if form.is_some_and(|x| x != default) {
+            facets.ignored.push("`form` attribute".to_string());
+        }
+    }
+
+    fn add_element(&mut self, definition: &Definition, element: &'a ElementType) {
+        if let Some(name) = &element.ref_ {
+            self.previous = Option::from(local_name(name));
+            if let Some(global) = find_type(&self.elements, &get_type_name(name)) {
+                let facets = self.get_element_facets(global);
+                self.insert(definition, (FieldKind::Element, local_name(name)), facets);
+            }
+            return;
+        }
+
+        let Some(name) = &element.name else {
+            return;
+        };
+
+        let mut facets = self.get_element_facets(element);
+        if !matches!(definition, Definition::Global(_)) {
+            self.check_form(&mut facets, element.form.as_ref(), false);
+        }
+        self.insert(definition, (FieldKind::Element, name.clone()), facets);
+
+        let local = match definition {
+            Definition::Global(namespace) => Definition::Element((namespace.clone(), name.clone())),
+            _ => Definition::Local(Box::new(definition.clone()), name.clone()),
+        };
+
+        self.previous = None;
+        for content in element.content.iter() {
+            if let ElementTypeContent::ComplexType(x) = content {
+                self.add_complex(&local, x);
+            }
+        }
+        self.previous = Option::from(name.clone());
+    }
+
+    fn add_wildcard(&mut self, definition: &Definition, wildcard: Wildcard) {
+        self.restrictions.entry(definition.clone()).or_default();
+        self.wildcards
+            .entry(definition.clone())
+            .or_default()
+            .push(wildcard);
+    }
+
+    fn add_any(&mut self, definition: &Definition, any: &Any) {
+        let target_namespace = self.schema.and_then(|x| x.target_namespace.as_ref());
+        let mut wildcard = get_wildcard(any, target_namespace);
+        wildcard.after = self.previous.clone();
+        self.add_wildcard(definition, wildcard);
+    }
+
+    fn add_any_attribute(&mut self, definition: &Definition, any: &AnyAttribute) {
+        let is_xml = |x: &BasicNamespaceListItemType| match x {
+            BasicNamespaceListItemType::String(x) => x == XML_NAMESPACE,
+            _ => false,
+        };
+
+        let excluded = any
+            .not_namespace
+            .as_ref()
+            .is_some_and(|x| x.0.iter().any(is_xml));
+        let allowed = match &any.namespace {
+            None | Some(NamespaceListType::Any) | Some(NamespaceListType::Other) => true,
+            Some(NamespaceListType::BasicNamespaceList(x)) => x.0.iter().any(is_xml),
+        };
+
+        self.restrictions.entry(definition.clone()).or_default();
+        if allowed && !excluded {
+            self.lang_definitions.insert(definition.clone());
+        }
+    }
+
+    fn add_open_content(&mut self, definition: &Definition, open_content: &OpenContent) {
+        let Some(any) = &open_content.any else {
+            return;
+        };
+
+        if open_content.mode == OpenContentModeType::None {
+            return;
+        }
+
+        let target_namespace = self.schema.and_then(|x| x.target_namespace.as_ref());
+        let wildcard = Wildcard {
+            process_contents: any.process_contents.clone(),
+            namespace: get_wildcard_namespace(&any.namespace, target_namespace),
+            field_type: FieldType {
+                name: String::new(),
+                min_occurrences: Some(0),
+                max_occurrences: Some(1),
+                facets: None,
+            },
+            open_content: Option::from(open_content.mode.clone()),
+            after: None,
+        };
+        self.add_wildcard(definition, wildcard);
+    }
+
+    fn add_attribute(&mut self, definition: &Definition, attribute: &'a AttributeType) {
+        let (name, mut facets) = match (&attribute.ref_, &attribute.name) {
+            (Some(name), _) => {
+                let Some(global) = find_type(&self.attributes, &get_type_name(name)) else {
+                    return;
+                };
+                (local_name(name), self.get_attribute_facets(global))
+            }
+            (None, Some(name)) => {
+                let mut facets = self.get_attribute_facets(attribute);
+                if !matches!(definition, Definition::Global(_)) {
+                    self.check_form(&mut facets, attribute.form.as_ref(), true);
+                }
+                (name.clone(), facets)
+            }
+            (None, None) => return,
+        };
+
+        if let Some(fixed) = &attribute.fixed {
+            facets.fixed = Option::from(unescape_value(fixed));
+        }
+
+        self.insert(definition, (FieldKind::Attribute, name), facets);
+    }
+
+    fn add_attribute_group(&mut self, definition: &Definition, group: &'a AttributeGroupType) {
+        if let Some(name) = &group.ref_ {
+            let name = get_type_name(name);
+            let Some(group) = find_type(&self.attribute_groups, &name).copied() else {
+                return;
+            };
+
+            if !self.expanded_groups.contains(&name) {
+                self.expanded_groups.push(name);
+                self.add_attribute_group(definition, group);
+                self.expanded_groups.pop();
+            }
+            return;
+        }
+
+        for content in group.content.iter() {
+            match content {
+                AttributeGroupTypeContent::Attribute(x) => self.add_attribute(definition, x),
+                AttributeGroupTypeContent::AttributeGroup(x) => {
+                    self.add_attribute_group(definition, x)
+                }
+                AttributeGroupTypeContent::AnyAttribute(x) => self.add_any_attribute(definition, x),
+                _ => {}
+            }
+        }
+    }
+
+    fn add_group(&mut self, definition: &Definition, group: &'a GroupType) {
+        if let Some(name) = &group.ref_ {
+            let name = get_type_name(name);
+            let Some(group) = find_type(&self.groups, &name).copied() else {
+                return;
+            };
+
+            if !self.expanded_groups.contains(&name) {
+                self.expanded_groups.push(name);
+                self.add_group(definition, group);
+                self.expanded_groups.pop();
+            }
+            return;
+        }
+
+        for content in group.content.iter() {
+            match content {
+                GroupTypeContent::Element(x) => self.add_element(definition, x),
+                GroupTypeContent::Any(x) => self.add_any(definition, x),
+                GroupTypeContent::Group(x)
+                | GroupTypeContent::All(x)
+                | GroupTypeContent::Choice(x)
+                | GroupTypeContent::Sequence(x) => self.add_group(definition, x),
+                _ => {}
+            }
+        }
+    }
+
+    fn add_base(&mut self, definition: &Definition, base: &QName) {
+        let name = get_type_name(base);
+        let Some(complex) = find_type(&self.complex_types, &name).copied() else {
+            return;
+        };
+
+        if !self.expanded_types.contains(&name) {
+            self.expanded_types.push(name);
+            self.add_complex(definition, complex);
+            self.expanded_types.pop();
+        }
+    }
+
+    fn add_derived_content(
+        &mut self,
+        definition: &Definition,
+        content: &'a RestrictionTypeContent,
+    ) {
+        match content {
+            RestrictionTypeContent::Group(x)
+            | RestrictionTypeContent::All(x)
+            | RestrictionTypeContent::Choice(x)
+            | RestrictionTypeContent::Sequence(x) => self.add_group(definition, x),
+            RestrictionTypeContent::Attribute(x) => self.add_attribute(definition, x),
+            RestrictionTypeContent::AttributeGroup(x) => self.add_attribute_group(definition, x),
+            RestrictionTypeContent::AnyAttribute(x) => self.add_any_attribute(definition, x),
+            _ => {}
+        }
+    }
+
+    fn add_extension_content(
+        &mut self,
+        definition: &Definition,
+        content: &'a ExtensionTypeContent,
+    ) {
+        match content {
+            ExtensionTypeContent::Group(x)
+            | ExtensionTypeContent::All(x)
+            | ExtensionTypeContent::Choice(x)
+            | ExtensionTypeContent::Sequence(x) => self.add_group(definition, x),
+            ExtensionTypeContent::Attribute(x) => self.add_attribute(definition, x),
+            ExtensionTypeContent::AttributeGroup(x) => self.add_attribute_group(definition, x),
+            ExtensionTypeContent::OpenContent(x) => self.add_open_content(definition, x),
+            ExtensionTypeContent::AnyAttribute(x) => self.add_any_attribute(definition, x),
+            _ => {}
+        }
+    }
+
+    fn add_simple_content(&mut self, definition: &Definition, content: &'a SimpleContent) {
+        for x in content.content.iter() {
+            match x {
+                SimpleContentContent::Extension(extension) => {
+                    self.add_base(definition, &extension.base);
+                    for x in extension.content.iter() {
+                        self.add_extension_content(definition, x);
+                    }
+                }
+                SimpleContentContent::Restriction(restriction) => {
+                    self.add_base(definition, &restriction.base);
+                    for x in restriction.content.iter() {
+                        self.add_derived_content(definition, x);
+                    }
+                }
+                SimpleContentContent::Annotation(_) => {}
+            }
+        }
+    }
+
+    fn add_complex_content(&mut self, definition: &Definition, content: &'a ComplexContent) {
+        for x in content.content.iter() {
+            match x {
+                ComplexContentContent::Extension(extension) => {
+                    self.add_base(definition, &extension.base);
+                    for x in extension.content.iter() {
+                        self.add_extension_content(definition, x);
+                    }
+                }
+                ComplexContentContent::Restriction(restriction) => {
+                    let wildcards = self.wildcards.get(definition).map_or(0, |x| x.len());
+                    self.add_base(definition, &restriction.base);
+                    if let Some(x) = self.wildcards.get_mut(definition) {
+                        x.truncate(wildcards);
+                    }
+
+                    self.previous = None;
+                    for x in restriction.content.iter() {
+                        self.add_derived_content(definition, x);
+                    }
+                }
+                ComplexContentContent::Annotation(_) => {}
+            }
+        }
+    }
+
+    fn add_complex(&mut self, definition: &Definition, complex: &'a ComplexBaseType) {
+        self.restrictions.entry(definition.clone()).or_default();
+        for content in complex.content.iter() {
+            match content {
+                ComplexBaseTypeContent::Group(x)
+                | ComplexBaseTypeContent::All(x)
+                | ComplexBaseTypeContent::Choice(x)
+                | ComplexBaseTypeContent::Sequence(x) => self.add_group(definition, x),
+                ComplexBaseTypeContent::Attribute(x) => self.add_attribute(definition, x),
+                ComplexBaseTypeContent::AttributeGroup(x) => {
+                    self.add_attribute_group(definition, x)
+                }
+                ComplexBaseTypeContent::SimpleContent(x) => self.add_simple_content(definition, x),
+                ComplexBaseTypeContent::ComplexContent(x) => {
+                    self.add_complex_content(definition, x)
+                }
+                ComplexBaseTypeContent::OpenContent(x) => self.add_open_content(definition, x),
+                ComplexBaseTypeContent::AnyAttribute(x) => self.add_any_attribute(definition, x),
+                _ => {}
+            }
+        }
+
+        if let Some(restriction) = get_simple_content_restriction(complex) {
+            let facets = resolve_facets(&restriction, &self.simple_types);
+            self.insert(definition, (FieldKind::Text, String::new()), facets);
+        }
+    }
+}
+
+fn get_restrictions(schemas: &Schemas) -> RestrictionBuilder<'_> {
+    let mut builder = RestrictionBuilder::default();
+    for (_, schema) in schemas.schemas() {
+        let namespace = &schema.target_namespace;
+        for content in schema.content.iter() {
+            let (name, restriction) = match content {
+                SchemaContent::SimpleType(x) => (&x.name, get_simple_restriction(x)),
+                SchemaContent::ComplexType(x) => (&x.name, get_simple_content_restriction(x)),
+                _ => continue,
+            };
+
+            if let (Some(name), Some(restriction)) = (name, restriction) {
+                let name = (namespace.clone(), name.clone());
+                builder.simple_types.insert(name, restriction);
+            }
+        }
+
+        for content in schema.content.iter() {
+            let name = match content {
+                SchemaContent::ComplexType(x) => &x.name,
+                SchemaContent::Group(x) => &x.name,
+                SchemaContent::AttributeGroup(x) => &x.name,
+                SchemaContent::Element(x) => &x.name,
+                SchemaContent::Attribute(x) => &x.name,
+                _ => continue,
+            };
+
+            let Some(name) = name else {
+                continue;
+            };
+
+            let name = (namespace.clone(), name.clone());
+            match content {
+                SchemaContent::ComplexType(x) => builder.complex_types.insert(name, x).is_none(),
+                SchemaContent::Group(x) => builder.groups.insert(name, x).is_none(),
+                SchemaContent::AttributeGroup(x) => {
+                    builder.attribute_groups.insert(name, x).is_none()
+                }
+                SchemaContent::Element(x) => builder.elements.insert(name, x).is_none(),
+                SchemaContent::Attribute(x) => builder.attributes.insert(name, x).is_none(),
+                _ => continue,
+            };
+        }
+    }
+
+    for (_, schema) in schemas.schemas() {
+        let namespace = &schema.target_namespace;
+        let global = Definition::Global(namespace.clone());
+        builder.schema = Option::from(schema);
+        for content in schema.content.iter() {
+            match content {
+                SchemaContent::Element(x) => builder.add_element(&global, x),
+                SchemaContent::Attribute(x) => builder.add_attribute(&global, x),
+                SchemaContent::ComplexType(x) => {
+                    if let Some(name) = &x.name {
+                        let definition = Definition::Type((namespace.clone(), name.clone()));
+                        builder.previous = None;
+                        builder.add_complex(&definition, x);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    builder
+}
+
+fn get_ident_namespace(schemas: &Schemas, ident: &Ident) -> Option<String> {
+    let info = schemas.get_namespace_info(ident.ns.as_ref()?)?;
+    info.namespace.as_ref().map(|x| x.to_string())
+}
+
+fn get_definitions(
+    schemas: &Schemas,
+    data_types: &DataTypes,
+    restrictions: &Restrictions,
+) -> HashMap<String, Definition> {
+    let items = &data_types.meta.types.items;
+    let mut definitions: HashMap<&Ident, Definition> = HashMap::new();
+    for ident in items.keys() {
+        let name = (get_ident_namespace(schemas, ident), ident.name.to_string());
+        let definition = match ident.type_ {
+            IdentType::Type => Definition::Type(name),
+            IdentType::ElementType => Definition::Element(name),
+            _ => continue,
+        };
+
+        if restrictions.contains_key(&definition) {
+            definitions.insert(ident, definition);
+        }
+    }
+
+    loop {
+        let mut found = vec![];
+        for (ident, definition) in definitions.iter() {
+            let Some(meta_type) = items.get(*ident) else {
+                continue;
+            };
+
+            let elements = match &meta_type.variant {
+                MetaTypeVariant::ComplexType(complex) => {
+                    if let Some(content) = &complex.content {
+                        found.push((content, definition.clone()));
+                    }
+                    continue;
+                }
+                MetaTypeVariant::All(group)
+                | MetaTypeVariant::Choice(group)
+                | MetaTypeVariant::Sequence(group) => &group.elements,
+                _ => continue,
+            };
+
+            for element in elements.iter() {
+                let ElementMetaVariant::Type(type_) = &element.variant else {
+                    continue;
+                };
+
+                if element.element_mode == ElementMode::Group {
+                    found.push((type_, definition.clone()));
+                } else if type_.type_ == IdentType::ElementType {
+                    let name = element.ident.name.to_string();
+                    let local = Definition::Local(Box::new(definition.clone()), name);
+                    found.push((type_, local));
+                }
+            }
+        }
+
+        let mut changed = false;
+        for (ident, definition) in found {
+            if !definitions.contains_key(ident) {
+                definitions.insert(ident, definition);
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    let mut names = HashMap::new();
+    for (ident, definition) in definitions {
+        if let Some(name) = get_struct_name(data_types, ident) {
+            names.insert(name, definition);
+        }
+    }
+
+    names
+}
+
+fn find_global_facets<'a>(
+    restrictions: &'a Restrictions,
+    key: &(FieldKind, String),
+) -> Option<&'a Facets> {
+    restrictions
+        .iter()
+        .filter(|(definition, _)| matches!(definition, Definition::Global(_)))
+        .find_map(|(_, declarations)| declarations.get(key))
+}
+
+fn apply_restrictions(structs: &mut [StructInfo], schemas: &Schemas, data_types: &DataTypes) {
+    let builder = get_restrictions(schemas);
+    let restrictions = &builder.restrictions;
+    let definitions = get_definitions(schemas, data_types, restrictions);
+    let empty = Declarations::new();
+    for structure in structs.iter_mut() {
+        let definition = definitions.get(&structure.name);
+        structure.allows_xml_lang =
+            definition.is_some_and(|x| builder.lang_definitions.contains(x));
+        structure.definition = definition.map(get_definition_name);
+        let declarations = definition
+            .and_then(|x| restrictions.get(x))
+            .unwrap_or(&empty);
+
+        for field in structure.fields.iter_mut() {
+            let name = match field.kind {
+                FieldKind::Text => String::new(),
+                _ => field.xml_name.clone(),
+            };
+            field.field_type.facets = declarations.get(&(field.kind, name)).cloned();
+
+            let Some(substitutes) = &mut field.substitutes else {
+                continue;
+            };
+
+            for (name, kind, branch_type) in substitutes.iter_mut() {
+                let key = (*kind, name.clone());
+                let facets = declarations.get(&key);
+                branch_type.facets = facets
+                    .or_else(|| find_global_facets(restrictions, &key))
+                    .cloned();
+            }
+        }
+    }
+}
+
+const WILDCARD_NAMESPACE: &str = "urn:xmlgenerator:wildcard";
+
+#[derive(Clone)]
+struct Wildcard {
+    process_contents: ProcessContentsType,
+    namespace: Option<String>,
+    field_type: FieldType,
+    open_content: Option<OpenContentModeType>,
+    after: Option<String>,
+}
+
+type Wildcards = HashMap<String, Vec<Wildcard>>;
+
+fn get_wildcard_namespace(
+    namespace: &Option<NamespaceListType>,
+    target_namespace: Option<&String>,
+) -> Option<String> {
+    let items = match namespace {
+        None | Some(NamespaceListType::Any) => return None,
+        Some(NamespaceListType::Other) => return Option::from(WILDCARD_NAMESPACE.to_string()),
+        Some(NamespaceListType::BasicNamespaceList(x)) => &x.0,
+    };
+
+    match items.first() {
+        Some(BasicNamespaceListItemType::String(x)) => Option::from(x.clone()),
+        Some(BasicNamespaceListItemType::TargetNamespace) => target_namespace.cloned(),
+        Some(BasicNamespaceListItemType::Local) | None => None,
+    }
+}
+
+fn get_wildcard(any: &Any, target_namespace: Option<&String>) -> Wildcard {
+    let max_occurrences = match any.max_occurs {
+        MaxOccurs::Bounded(x) => Some(x as u64),
+        MaxOccurs::Unbounded => None,
+    };
+
+    Wildcard {
+        process_contents: any.process_contents.clone(),
+        namespace: get_wildcard_namespace(&any.namespace, target_namespace),
+        field_type: FieldType {
+            name: String::new(),
+            min_occurrences: Some(any.min_occurs as u64),
+            max_occurrences,
+            facets: None,
+        },
+        open_content: None,
+        after: None,
+    }
+}
+
+fn get_wildcards(schemas: &Schemas, data_types: &DataTypes) -> Wildcards {
+    let builder = get_restrictions(schemas);
+    let wildcards = builder.wildcards;
+    let definitions = get_definitions(schemas, data_types, &builder.restrictions);
+    definitions
+        .into_iter()
+        .filter_map(|(name, definition)| Option::from((name, wildcards.get(&definition)?.clone())))
+        .collect()
+}
+
+#[derive(Default)]
+struct DeclarationVisitor {
+    error: Option<String>,
+}
+
+impl SchemaVisitor for DeclarationVisitor {
+    fn visit_element(&mut self, element: &ElementType) {
+        if self.error.is_some() {
+            return;
+        }
+
+        if let (Some(name), Some(reference)) = (&element.name, &element.ref_) {
+            self.error = Option::from(format!(
+                "Element `{}` declares both a name and a ref to `{}`",
+                name,
+                local_name(reference)
+            ));
+        }
+    }
+}
+
+fn validate_declarations(schemas: &Schemas) -> Result<(), XMLGeneratorError> {
+    let mut visitor = DeclarationVisitor::default();
+    walk_schemas(&mut visitor, schemas);
+
+    match visitor.error {
+        Some(error) => Err(InvalidInputError(error)),
+        None => Ok(()),
+    }
+}
+
+#[derive(Default)]
+struct PrefixVisitor {
+    bindings: Vec<(NamespacePrefix, Namespace)>,
+}
+
+impl PrefixVisitor {
+    fn add_binding(&mut self, name: Option<&QName>) {
+        let Some(name) = name else {
+            return;
+        };
+
+        let (Some(prefix), Some(namespace)) = (name.prefix(), name.namespace()) else {
+            return;
+        };
+
+        let binding = (NamespacePrefix::new(prefix.to_vec()), namespace.clone());
+        if !self.bindings.contains(&binding) {
+            self.bindings.push(binding);
+        }
+    }
+}
+
+impl SchemaVisitor for PrefixVisitor {
+    fn visit_element(&mut self, element: &ElementType) {
+        self.add_binding(element.ref_.as_ref());
+        self.add_binding(element.type_.as_ref());
+    }
+
+    fn visit_attribute(&mut self, attribute: &AttributeType) {
+        self.add_binding(attribute.ref_.as_ref());
+        self.add_binding(attribute.type_.as_ref());
+    }
+}
+
+fn is_optional_recursion(structure: &StructInfo, field: &FieldInfo) -> bool {
+    field.field_type.name == structure.name && field.field_type.min_occurrences == Some(0)
+}
+
+fn get_field_struct<'a>(structs: &'a [StructInfo], field: &str) -> Option<&'a StructInfo> {
+    for structure in structs.iter() {
+        if structure.name == field {
+            return Option::from(structure);
+        }
+    }
+
+    None
+}
+
+fn get_references(structure: &StructInfo) -> Vec<&String> {
+    let mut references = vec![];
+    for field in structure.fields.iter() {
+        if is_optional_recursion(structure, field) {
+            continue;
+        }
+
+        if !references.contains(&&field.field_type.name) {
+            references.push(&field.field_type.name);
+        }
+
+        for (_, _, field_type) in field.substitutes.iter().flatten() {
+            if !references.contains(&&field_type.name) {
+                references.push(&field_type.name);
+            }
+        }
+    }
+
+    references
+}
+
+fn reaches(structs: &[StructInfo], from: &StructInfo, to: &StructInfo) -> bool {
+    let mut visited: Vec<&String> = vec![];
+    let mut pending = get_references(from);
+    while let Some(name) = pending.pop() {
+        if *name == to.name {
+            return true;
+        }
+
+        if visited.contains(&name) {
+            continue;
+        }
+        visited.push(name);
+
+        if let Some(structure) = get_field_struct(structs, name) {
+            pending.extend(get_references(structure));
+        }
+    }
+
+    false
+}
+
+fn find_side_cycles(structs: &[StructInfo]) -> Vec<&StructInfo> {
+    let mut side_cycles = vec![];
+    for structure in structs.iter() {
+        if !reaches(structs, structure, structure) {
+            continue;
+        }
+
+        let cycle: Vec<&StructInfo> = structs
+            .iter()
+            .filter(|x| reaches(structs, structure, x) && reaches(structs, x, structure))
+            .collect();
+
+        let referenced = structs.iter().any(|x| {
+            !cycle.contains(&x)
+                && get_references(x)
+                    .iter()
+                    .any(|name| cycle.iter().any(|y| y.name == **name))
+        });
+
+        if !referenced {
+            side_cycles.push(structure);
+        }
+    }
+
+    side_cycles
+}
+
+fn find_roots(structs: &[StructInfo]) -> Vec<&StructInfo> {
+    let side_cycles = find_side_cycles(structs);
+
+    let mut all_fields: Vec<&String> = vec![];
+    for structure in structs.iter() {
+        if side_cycles.contains(&structure) {
+            continue;
+        }
+
+        for name in get_references(structure) {
+            if !all_fields.contains(&name) {
+                all_fields.push(name);
+            }
+        }
+    }
+    let mut dep_structs = side_cycles;
+    for field in all_fields.iter() {
+        if let Some(structure) = get_field_struct(structs, field)
+            && !dep_structs.contains(&structure)
+        {
+            dep_structs.push(structure);
+        }
+    }
+
+    for structure in structs.iter() {
+        if let Some(base) = &structure.base
+            && all_fields.contains(&base)
+            && !dep_structs.contains(&structure)
+        {
+            dep_structs.push(structure);
+        }
+    }
+
+    for structure in structs.iter() {
+        if let Some(base) = &structure.base
+            && let Some(base) = get_field_struct(structs, base)
+            && base.element_name.is_none()
+            && !dep_structs.contains(&base)
+        {
+            dep_structs.push(base);
+        }
+    }
+
+    for structure in structs.iter() {
+        if let Some(base) = &structure.base
+            && let Some(base) = get_field_struct(structs, base)
+            && base.element_name.is_some()
+            && structure.element_name.is_none()
+            && !dep_structs.contains(&structure)
+        {
+            dep_structs.push(structure);
+        }
+    }
+
+    let mut independent_structs = vec![];
+
+    for structure in structs.iter() {
+        if !dep_structs.contains(&structure) {
+            independent_structs.push(structure);
+        }
+    }
+
+    independent_structs
+}
+
+fn find_root(structs: &[StructInfo]) -> Result<&StructInfo, XMLGeneratorError> {
+    let independent_structs = find_roots(structs);
+
+    if independent_structs.is_empty() {
+        return Err(InvalidInputError("No independent structs found".to_string()));
+    }
+
+    if independent_structs.len() > 1 {
+        return Err(InvalidInputError("Multiple independent structs found!".to_string()));
+    }
+
+    for structure in structs.iter() {
+        if independent_structs.contains(&structure) {
+            return Ok(structure);
+        }
+    }
+
+    unreachable!();
+}
+
+#[derive(Clone, Copy)]
+struct Budget {
+    bytes_used: usize,
+    namespaces: usize,
+    uses_xsi: bool,
+}
+
+struct GeneratorState<'a> {
+    structs: &'a [StructInfo],
+    wildcards: &'a Wildcards,
+    config: &'a GeneratorConfig,
+    rng: StdRng,
+    uses_xsi: bool,
+    namespaces: Vec<XmlNamespace>,
+    bytes_used: usize,
+    budget_exceeded: bool,
+    elements_used: usize,
+    workarounds: Vec<String>,
+    warnings: Vec<String>,
+    violation: Option<String>,
+    force_violation: bool,
+    path: Vec<String>,
+    definitions: Vec<String>,
+    placed_wildcards: Vec<(&'a [Wildcard], Vec<bool>)>,
+    coverage: HashMap<(String, String), usize>,
+}
+
+impl<'a> GeneratorState<'a> {
+    fn new(
+        structs: &'a [StructInfo],
+        wildcards: &'a Wildcards,
+        config: &'a GeneratorConfig,
+    ) -> Self {
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+
+        GeneratorState {
+            structs,
+            wildcards,
+            config,
+            rng,
+            uses_xsi: false,
+            namespaces: vec![],
+            bytes_used: 0,
+            budget_exceeded: false,
+            elements_used: 0,
+            workarounds: vec![],
+            warnings: vec![],
+            violation: None,
+            force_violation: false,
+            path: vec![],
+            definitions: vec![],
+            placed_wildcards: vec![],
+            coverage: HashMap::new(),
+        }
+    }
+
+    fn budget(&self) -> Budget {
+        Budget {
+            bytes_used: self.bytes_used,
+            namespaces: self.namespaces.len(),
+            uses_xsi: self.uses_xsi,
+        }
+    }
+
+    fn restore(&mut self, budget: Budget) {
+        self.bytes_used = budget.bytes_used;
+        self.namespaces.truncate(budget.namespaces);
+        self.uses_xsi = budget.uses_xsi;
+        self.budget_exceeded = false;
+    }
+
+    fn fallback(&mut self, workaround: String) {
+        warn!("{}", workaround);
+        if !self.workarounds.contains(&workaround) {
+            self.workarounds.push(workaround);
+        }
+    }
+
+    fn warning(&mut self, warning: String) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    fn violate(&mut self, violation: impl FnOnce() -> String) -> bool {
+        if self.config.mode != GenerationMode::Invalid || self.violation.is_some() {
+            return false;
+        }
+
+        if !self.force_violation && !self.rng.random_bool(0.5) {
+            return false;
+        }
+
+        self.violation = Option::from(violation());
+        true
+    }
+
+    fn cover(&mut self, particle: &str, count: usize) {
+        let Some(definition) = self.definitions.last() else {
+            return;
+        };
+
+        let key = (definition.clone(), particle.to_string());
+        *self.coverage.entry(key).or_insert(0) += count;
+    }
+
+    fn report(&self) -> GenerationReport {
+        GenerationReport {
+            used_fallbacks: !self.workarounds.is_empty(),
+            workarounds: self.workarounds.clone(),
+            warnings: self.warnings.clone(),
+            violation: self.violation.clone(),
+            coverage: CoverageSummary {
+                particles: self.coverage.clone(),
+            },
+        }
+    }
+}
+
+fn make_fake<Output: fake::Dummy<Faker> + ToString>(rng: &mut StdRng) -> Option<String> {
+    Option::from(Faker.fake_with_rng::<Output, _>(rng).to_string())
+}
+
+const FLOAT_SPECIAL_VALUES: [&str; 3] = ["INF", "-INF", "NaN"];
+
+fn make_float<Output: fake::Dummy<Faker> + ToString>(state: &mut GeneratorState) -> Option<String> {
+    if state.config.float_special_values && state.rng.random_bool(0.05) {
+        let index = state.rng.random_range(0..FLOAT_SPECIAL_VALUES.len());
+        return Option::from(FLOAT_SPECIAL_VALUES[index].to_string());
+    }
+
+    let value = make_fake::<Output>(&mut state.rng)?;
+    let value = match (state.config.fraction_digits, value.parse::<f64>()) {
+        (Some(digits), Ok(x)) => format!("{:.*}", digits as usize, x),
+        _ => value,
+    };
+
+    Option::from(normalise_decimal(value, state.config))
+}
+
+fn normalise_decimal(value: String, config: &GeneratorConfig) -> String {
+    let (sign, digits) = match value.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", value.as_str()),
+    };
+
+    if !digits.starts_with(|x: char| x.is_ascii_digit()) {
+        return value;
+    }
+
+    let mut digits = digits;
+    if config.canonical_decimals && digits.contains('.') {
+        digits = digits.trim_end_matches('0').trim_end_matches('.');
+    }
+
+    if digits.chars().all(|x| x == '0' || x == '.') {
+        return "0".to_string();
+    }
+
+    format!("{}{}", sign, digits)
+}
+
+fn get_string(type_name: &str, state: &mut GeneratorState) -> Option<String> {
+    let rng = &mut state.rng;
+    match type_name {
+        "i8" => make_fake::<i8>(rng),
+        "u8" => make_fake::<u8>(rng),
+        "i16" => make_fake::<i16>(rng),
+        "u16" => make_fake::<u16>(rng),
+        "i32" => make_fake::<i32>(rng),
+        "u32" => make_fake::<u32>(rng),
+        "i64" => make_fake::<i64>(rng),
+        "u64" => make_fake::<u64>(rng),
+        "i128" => make_fake::<i128>(rng),
+        "u128" => make_fake::<u128>(rng),
+        "isize" => make_fake::<isize>(rng),
+        "usize" => make_fake::<usize>(rng),
+        "f32" => make_float::<f32>(state),
+        "f64" => make_float::<f64>(state),
+        "bool" => make_fake::<bool>(rng),
+        "char" => make_fake::<char>(rng),
+        "String" => make_fake::<String>(rng),
+        _ => None,
+    }
+}
+
+fn get_natural_bounds(type_name: &str) -> Option<(i128, i128)> {
+    match type_name {
+        "i8" => Some((i8::MIN as i128, i8::MAX as i128)),
+        "u8" => Some((u8::MIN as i128, u8::MAX as i128)),
+        "i16" => Some((i16::MIN as i128, i16::MAX as i128)),
+        "u16" => Some((u16::MIN as i128, u16::MAX as i128)),
+        "i32" => Some((i32::MIN as i128, i32::MAX as i128)),
+        "u32" => Some((u32::MIN as i128, u32::MAX as i128)),
+        "i64" => Some((i64::MIN as i128, i64::MAX as i128)),
+        "u64" => Some((u64::MIN as i128, u64::MAX as i128)),
+        "i128" => Some((i128::MIN, i128::MAX)),
+        "u128" => Some((0, i128::MAX)),
+        "isize" => Some((isize::MIN as i128, isize::MAX as i128)),
+        "usize" => Some((usize::MIN as i128, usize::MAX as i128)),
+        _ => None,
+    }
+}
+
+const INTEGER_MAX_DIGITS: u32 = 30;
+
+fn get_bounded_integer(
+    type_name: &str,
+    facets: &Facets,
+    state: &mut GeneratorState,
+) -> Option<String> {
+    let unbounded = facets.base_type.as_deref() == Some("integer");
+    let (mut natural_min, mut natural_max) = match unbounded {
+        true => (-i128::MAX, i128::MAX),
+        false => get_natural_bounds(type_name)?,
+    };
+    if let Some(bound) = facets.total_digits.map(get_digit_bound) {
+        natural_min = natural_min.max(-bound);
+        natural_max = natural_max.min(bound);
+    }
+
+    let min = facets
+        .lower_bound(0)
+        .map_or(natural_min, |x| x.max(natural_min));
+    let max = facets
+        .upper_bound(0)
+        .map_or(natural_max, |x| x.min(natural_max));
+    if min > max {
+        state.fallback(format!(
+            "Facets exclude every value of type `{}`, ignoring the facets",
+            type_name
+        ));
+        return None;
+    }
+
+    if unbounded {
+        let bound = get_digit_bound(state.rng.random_range(1..=INTEGER_MAX_DIGITS));
+        let (lower, upper) = (min.max(-bound), max.min(bound));
+        if lower <= upper {
+            return Option::from(state.rng.random_range(lower..=upper).to_string());
+        }
+    }
+
+    Option::from(state.rng.random_range(min..=max).to_string())
+}
+
+fn get_out_of_bounds_value(
+    type_name: &str,
+    name: &str,
+    facets: &Facets,
+    state: &mut GeneratorState,
+) -> Option<String> {
+    if get_natural_bounds(type_name).is_none() && !matches!(type_name, "f32" | "f64") {
+        return None;
+    }
+
+    let value = match (facets.lower_bound(0), facets.upper_bound(0)) {
+        (_, Some(max)) => max.checked_add(1)?,
+        (Some(min), None) => min.checked_sub(1)?,
+        (None, None) => return None,
+    };
+
+    let violated =
+        state.violate(|| format!("Value `{}` of `{}` is outside its bounds", value, name));
+    violated.then(|| value.to_string())
+}
+
+fn get_enumeration(
+    facets: &Facets,
+    strategy: EnumerationStrategy,
+    rng: &mut StdRng,
+) -> Option<String> {
+    let count = facets.enumerations.len();
+    if count == 0 {
+        return None;
+    }
+
+    let index = match strategy {
+        EnumerationStrategy::Uniform => rng.random_range(0..count),
+        EnumerationStrategy::WeightedFirst => {
+            let weights = WeightedIndex::new((1..=count).rev()).ok()?;
+            rng.sample(weights)
+        }
+    };
+    Option::from(facets.enumerations[index].clone())
+}
+
+fn get_digit_bound(digits: u32) -> i128 {
+    10i128.checked_pow(digits).map_or(i128::MAX, |x| x - 1)
+}
+
+const DECIMAL_INTEGER_DIGITS: u32 = 6;
+const DECIMAL_FRACTION_DIGITS: u32 = 2;
+
+/// Splits the `totalDigits` of a decimal without `fractionDigits` at random
+/// between its integer and fractional parts, keeping enough integer digits to
+/// reach the bounding facets.
+fn split_total_digits(total: u32, facets: &Facets, state: &mut GeneratorState) -> (u32, u32) {
+    let nearest = match (facets.lower_bound(0), facets.upper_bound(0)) {
+        (Some(min), _) if min > 0 => min,
+        (_, Some(max)) if max < 0 => -max,
+        _ => 0,
+    };
+    let needed = if nearest == 0 {
+        0
+    } else {
+        nearest.unsigned_abs().ilog10() + 1
+    };
+
+    let available = total - needed.min(total);
+    let available = state
+        .config
+        .fraction_digits
+        .map_or(available, |x| available.min(x));
+    let fraction_digits = state.rng.random_range(0..=available);
+    (total - fraction_digits, fraction_digits)
+}
+
+fn get_bounded_decimal(
+    type_name: &str,
+    facets: &Facets,
+    state: &mut GeneratorState,
+) -> Option<String> {
+    if !matches!(type_name, "f32" | "f64") {
+        return None;
+    }
+
+    if facets.lower_bound(0).is_none()
+        && facets.upper_bound(0).is_none()
+        && facets.total_digits.is_none()
+        && facets.fraction_digits.is_none()
+    {
+        return None;
+    }
+
+    let (integer_digits, fraction_digits) = match (facets.total_digits, facets.fraction_digits) {
+        (Some(total), None) => split_total_digits(total, facets, state),
+        _ => {
+            let fraction_digits = facets
+                .fraction_digits
+                .or(state.config.fraction_digits)
+                .unwrap_or(DECIMAL_FRACTION_DIGITS);
+            let fraction_digits = facets
+                .total_digits
+                .map_or(fraction_digits, |x| fraction_digits.min(x));
+            let integer_digits = facets
+                .total_digits
+                .map_or(DECIMAL_INTEGER_DIGITS, |x| x - fraction_digits);
+            (integer_digits, fraction_digits)
+        }
+    };
+
+    if facets.min_decimal.is_some() || facets.max_decimal.is_some() {
+        return get_scaled_decimal(type_name, facets, integer_digits, fraction_digits, state);
+    }
+
+    let bound = get_digit_bound(integer_digits);
+    let min = facets.min.map_or(-bound, |x| x.max(-bound));
+    let max = facets.max.map_or(bound, |x| x.min(bound));
+    if min > max {
+        state.fallback(format!(
+            "Facets exclude every value of type `{}`, ignoring the facets",
+            type_name
+        ));
+        return None;
+    }
+
+    let integer = state.rng.random_range(min..=max);
+    let at_max = integer >= 0 && facets.max == Some(integer);
+    let at_min = integer < 0 && facets.min == Some(integer);
+    if fraction_digits == 0 || at_max || at_min {
+        return Option::from(integer.to_string());
+    }
+
+    let length = state.rng.random_range(1..=fraction_digits);
+    let fraction: String = (0..length)
+        .map(|_| char::from(b'0' + state.rng.random_range(0..10)))
+        .collect();
+    let value = format!("{}.{}", integer, fraction);
+    Option::from(normalise_decimal(value, state.config))
+}
+
+/// Picks a decimal with `fraction_digits` digits after the point between
+/// bounds that have fractional parts themselves. Without a `fractionDigits`
+/// or `totalDigits` facet, the precision grows to that of the bounds.
+fn get_scaled_decimal(
+    type_name: &str,
+    facets: &Facets,
+    integer_digits: u32,
+    fraction_digits: u32,
+    state: &mut GeneratorState,
+) -> Option<String> {
+    let scale = match facets.fraction_digits.or(facets.total_digits) {
+        Some(_) => fraction_digits,
+        None => [facets.min_decimal, facets.max_decimal]
+            .iter()
+            .flatten()
+            .fold(fraction_digits, |scale, x| scale.max(x.scale)),
+    };
+
+    let bound = get_digit_bound(integer_digits + scale);
+    let min = facets.lower_bound(scale).map_or(-bound, |x| x.max(-bound));
+    let max = facets.upper_bound(scale).map_or(bound, |x| x.min(bound));
+    if min > max {
+        state.fallback(format!(
+            "Facets exclude every value of type `{}`, ignoring the facets",
+            type_name
+        ));
+        return None;
+    }
+
+    let value = state.rng.random_range(min..=max);
+    let divisor = 10i128.checked_pow(scale)?;
+    let sign = if value < 0 { "-" } else { "" };
+    let (integer, fraction) = (value.abs() / divisor, value.abs() % divisor);
+    let value = match scale {
+        0 => format!("{}{}", sign, integer),
+        _ => format!(
+            "{}{}.{:0width$}",
+            sign,
+            integer,
+            fraction,
+            width = scale as usize
+        ),
+    };
+    Option::from(normalise_decimal(value, state.config))
+}
+
+const PATTERN_MAX_REPEAT: u32 = 8;
+
+fn get_pattern_string(facets: &Facets, state: &mut GeneratorState) -> Option<String> {
+    let pattern = facets.pattern.as_ref()?;
+    match rand_regex::Regex::compile(pattern, PATTERN_MAX_REPEAT) {
+        Ok(regex) => Option::from(state.rng.sample::<String, _>(&regex)),
+        Err(err) => {
+            state.fallback(format!(
+                "Unsupported pattern `{}`, ignoring it: {}",
+                pattern, err
+            ));
+            None
+        }
+    }
+}
+
+const LANGUAGES: [&str; 6] = ["en", "fr", "de", "es", "zh", "ja"];
+const LANGUAGE_SUBTAGS: [&str; 8] = ["US", "GB", "CA", "FR", "DE", "MX", "Hant", "Hans"];
+
+fn make_language(rng: &mut StdRng) -> String {
+    let language = LANGUAGES[rng.random_range(0..LANGUAGES.len())];
+    if rng.random_bool(0.5) {
+        return language.to_string();
+    }
+
+    let subtag = LANGUAGE_SUBTAGS[rng.random_range(0..LANGUAGE_SUBTAGS.len())];
+    format!("{}-{}", language, subtag)
+}
+
+fn make_timezone(rng: &mut StdRng) -> String {
+    if rng.random_bool(0.5) {
+        return "Z".to_string();
+    }
+
+    let sign = if rng.random_bool(0.5) { '+' } else { '-' };
+    let hours = rng.random_range(0..=14);
+    let minutes = if hours == 14 {
+        0
+    } else {
+        15 * rng.random_range(0..4)
+    };
+    format!("{}{:02}:{:02}", sign, hours, minutes)
+}
+
+fn use_timezone(mode: TimezoneMode, rng: &mut StdRng) -> bool {
+    match mode {
+        TimezoneMode::Always => true,
+        TimezoneMode::Never => false,
+        TimezoneMode::Random => rng.random_bool(0.5),
+    }
+}
+
+fn make_date(rng: &mut StdRng) -> String {
+    format!(
+        "{:04}-{:02}-{:02}",
+        rng.random_range(1970..=2100),
+        rng.random_range(1..=12),
+        rng.random_range(1..=28),
+    )
+}
+
+fn make_time(rng: &mut StdRng) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        rng.random_range(0..24),
+        rng.random_range(0..60),
+        rng.random_range(0..60),
+    )
+}
+
+const SECONDS_PER_DAY: i128 = 24 * 60 * 60;
+
+fn make_bounded_time(facets: &Facets, mode: TimezoneMode, rng: &mut StdRng) -> Option<String> {
+    let min = facets.min.unwrap_or(0).max(0);
+    let max = facets.max.unwrap_or(SECONDS_PER_DAY - 1).min(SECONDS_PER_DAY - 1);
+    if min > max {
+        return None;
+    }
+
+    let seconds = rng.random_range(min..=max);
+    let time = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+
+    let timezone = match &facets.bound_timezone {
+        Some(timezone) => timezone.as_str(),
+        None if mode == TimezoneMode::Always => "Z",
+        None => "",
+    };
+    Option::from(time + timezone)
+}
+
+fn add_timezone(value: String, timezone: bool, rng: &mut StdRng) -> String {
+    if timezone {
+        value + &make_timezone(rng)
+    } else {
+        value
+    }
+}
+
+fn make_date_time(timezone: bool, rng: &mut StdRng) -> String {
+    let date_time = format!("{}T{}", make_date(rng), make_time(rng));
+    add_timezone(date_time, timezone, rng)
+}
+
+const DEFAULT_MAX_OCTETS: usize = 8;
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn make_octets(facets: &Facets, rng: &mut StdRng) -> Vec<u8> {
+    let min = facets.min_length.unwrap_or(1);
+    let max = facets.max_length.unwrap_or(DEFAULT_MAX_OCTETS).max(min);
+    let length = rng.random_range(min..=max);
+    (0..length).map(|_| rng.random()).collect()
+}
+
+fn make_hex_binary(facets: &Facets, lowercase: bool, rng: &mut StdRng) -> String {
+    make_octets(facets, rng)
+        .iter()
+        .map(|x| match lowercase {
+            true => format!("{:02x}", x),
+            false => format!("{:02X}", x),
+        })
+        .collect()
+}
+
+fn make_base64_binary(facets: &Facets, rng: &mut StdRng) -> String {
+    let mut encoded = String::new();
+    for chunk in make_octets(facets, rng).chunks(3) {
+        let mut bytes = [0; 3];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for index in 0..4 {
+            if index > chunk.len() {
+                encoded.push('=');
+            } else {
+                let sextet = (bits >> (18 - 6 * index)) & 0x3F;
+                encoded.push(char::from(BASE64_ALPHABET[sextet as usize]));
+            }
+        }
+    }
+
+    encoded
+}
+
+fn get_timezone_mode(facets: &Facets, mode: TimezoneMode) -> TimezoneMode {
+    match facets.explicit_timezone.as_deref() {
+        Some("required") => TimezoneMode::Always,
+        Some("prohibited") => TimezoneMode::Never,
+        _ => mode,
+    }
+}
+
+fn get_builtin_string(
+    facets: &Facets,
+    mode: TimezoneMode,
+    lowercase_hex: bool,
+    rng: &mut StdRng,
+) -> Option<String> {
+    let mode = get_timezone_mode(facets, mode);
+    match facets.base_type.as_deref() {
+        Some("language") => Option::from(make_language(rng)),
+        Some("dateTime") => {
+            let timezone = use_timezone(mode, rng);
+            Option::from(make_date_time(timezone, rng))
+        }
+        Some("dateTimeStamp") => Option::from(make_date_time(true, rng)),
+        Some("date") => {
+            let timezone = use_timezone(mode, rng);
+            let date = make_date(rng);
+            Option::from(add_timezone(date, timezone, rng))
+        }
+        Some("time") if facets.min.is_some() || facets.max.is_some() => {
+            make_bounded_time(facets, mode, rng).or_else(|| {
+                let timezone = use_timezone(mode, rng);
+                Option::from(add_timezone(make_time(rng), timezone, rng))
+            })
+        }
+        Some("time") => {
+            let timezone = use_timezone(mode, rng);
+            let time = make_time(rng);
+            Option::from(add_timezone(time, timezone, rng))
+        }
+        Some("hexBinary") => Option::from(make_hex_binary(facets, lowercase_hex, rng)),
+        Some("base64Binary") => Option::from(make_base64_binary(facets, rng)),
+        _ => None,
+    }
+}
+
+enum TextKind {
+    FirstName,
+    LastName,
+    Name,
+    City,
+    Country,
+}
+
+fn get_text_kind(xml_name: &str) -> Option<TextKind> {
+    let name = xml_name.to_lowercase().replace(['_', '-', '.'], "");
+    match name.as_str() {
+        "firstname" | "givenname" | "forename" => Some(TextKind::FirstName),
+        "lastname" | "surname" | "familyname" => Some(TextKind::LastName),
+        "name" | "fullname" => Some(TextKind::Name),
+        "city" | "town" => Some(TextKind::City),
+        "country" => Some(TextKind::Country),
+        _ => None,
+    }
+}
+
+fn make_text<L: CityNameGenFn>(kind: &TextKind, locale: L, rng: &mut StdRng) -> String {
+    match kind {
+        TextKind::FirstName => FirstName(locale).fake_with_rng(rng),
+        TextKind::LastName => LastName(locale).fake_with_rng(rng),
+        TextKind::Name => Name(locale).fake_with_rng(rng),
+        TextKind::City => CityName(locale).fake_with_rng(rng),
+        TextKind::Country => CountryName(locale).fake_with_rng(rng),
+    }
+}
+
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+fn is_xml_lang(field: &FieldInfo) -> bool {
+    field.kind == FieldKind::Attribute
+        && field.xml_name == "lang"
+        && field.namespace.as_ref().is_some_and(|x| x.uri == XML_NAMESPACE)
+}
+
+fn get_language_tag(locale: Option<Locale>) -> &'static str {
+    match locale {
+        None | Some(Locale::En) => "en",
+        Some(Locale::FrFr) => "fr-FR",
+        Some(Locale::DeDe) => "de-DE",
+        Some(Locale::ItIt) => "it-IT",
+        Some(Locale::PtBr) => "pt-BR",
+        Some(Locale::JaJp) => "ja-JP",
+        Some(Locale::ZhCn) => "zh-CN",
+    }
+}
+
+fn get_localised_string(field: &FieldInfo, state: &mut GeneratorState) -> Option<String> {
+    let locale = state.config.locale?;
+    if field.field_type.name != "String" {
+        return None;
+    }
+
+    let kind = get_text_kind(&field.xml_name)?;
+    let rng = &mut state.rng;
+    let text = match locale {
+        Locale::En => make_text(&kind, EN, rng),
+        Locale::FrFr => make_text(&kind, FR_FR, rng),
+        Locale::DeDe => make_text(&kind, DE_DE, rng),
+        Locale::ItIt => make_text(&kind, IT_IT, rng),
+        Locale::PtBr => make_text(&kind, PT_BR, rng),
+        Locale::JaJp => make_text(&kind, JA_JP, rng),
+        Locale::ZhCn => make_text(&kind, ZH_CN, rng),
+    };
+
+    Option::from(text)
+}
+
+enum NameHint {
+    Email,
+    Phone,
+    PostCode,
+    Url,
+    Uuid,
+}
+
+fn get_name_hint(xml_name: &str) -> Option<NameHint> {
+    let name = xml_name.to_lowercase().replace(['_', '-', '.'], "");
+    if name.contains("email") {
+        Some(NameHint::Email)
+    } else if name.contains("phone") {
+        Some(NameHint::Phone)
+    } else if name.contains("zip") || name.contains("postcode") || name.contains("postalcode") {
+        Some(NameHint::PostCode)
+    } else if name.contains("url") || name.contains("website") {
+        Some(NameHint::Url)
+    } else if name.contains("uuid") || name.contains("guid") {
+        Some(NameHint::Uuid)
+    } else {
+        None
+    }
+}
+
+fn make_uuid(rng: &mut StdRng) -> String {
+    let bits: u128 = rng.random();
+    let mask = (0xF << 76) | (0x3 << 62);
+    let bits = (bits & !mask) | (0x4 << 76) | (0x2 << 62);
+    let hex = format!("{:032x}", bits);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+fn get_hinted_string(field: &FieldInfo, state: &mut GeneratorState) -> Option<String> {
+    if !state.config.name_heuristics || field.field_type.name != "String" {
+        return None;
+    }
+
+    let rng = &mut state.rng;
+    let text = match get_name_hint(&field.xml_name)? {
+        NameHint::Email => SafeEmail(EN).fake_with_rng(rng),
+        NameHint::Phone => PhoneNumber(EN).fake_with_rng(rng),
+        NameHint::PostCode => ZipCode(EN).fake_with_rng(rng),
+        NameHint::Url => {
+            let word: String = Word(EN).fake_with_rng(rng);
+            let suffix: String = DomainSuffix(EN).fake_with_rng(rng);
+            format!("https://www.{}.{}", word, suffix)
+        }
+        NameHint::Uuid => make_uuid(rng),
+    };
+
+    Option::from(text)
+}
+
+fn apply_white_space(value: String, facets: &Facets) -> String {
+    match facets.white_space.as_deref() {
+        Some("replace") => value.replace(['\t', '\n', '\r'], " "),
+        Some("collapse") => value.split_whitespace().collect::<Vec<_>>().join(" "),
+        _ => value,
+    }
+}
+
+fn fit_length(value: String, facets: &Facets, rng: &mut StdRng) -> String {
+    let mut value = value;
+    if let Some(max_length) = facets.max_length {
+        value = value.chars().take(max_length).collect();
+        if facets.white_space.as_deref() == Some("collapse") {
+            value = value.trim_end().to_string();
+        }
+    }
+
+    if let Some(min_length) = facets.min_length {
+        let missing = min_length.saturating_sub(value.chars().count());
+        value.extend((0..missing).map(|_| char::from(rng.sample(Alphanumeric))));
+    }
+
+    value
+}
+
+fn cap_text_length(value: String, min_length: Option<usize>, config: &GeneratorConfig) -> String {
+    match config.max_text_length {
+        Some(max_text_length) => {
+            let max_text_length = max_text_length.max(min_length.unwrap_or(0));
+            value.chars().take(max_text_length).collect()
+        }
+        None => value,
+    }
+}
+
+fn fit_text(
+    value: String,
+    field: &FieldInfo,
+    facets: Option<&Facets>,
+    state: &mut GeneratorState,
+) -> String {
+    if field.field_type.name != "String" {
+        return value;
+    }
+
+    let Some(facets) = facets else {
+        return cap_text_length(value, None, state.config);
+    };
+
+    let value = apply_white_space(value, facets);
+    let value = fit_length(value, facets, &mut state.rng);
+    cap_text_length(value, facets.min_length, state.config)
+}
+
+fn get_pool_value(name: &str, state: &mut GeneratorState) -> Option<String> {
+    let pool = state.config.value_pools.get(name)?;
+    if pool.is_empty() {
+        return None;
+    }
+
+    let index = state.rng.random_range(0..pool.len());
+    Option::from(pool[index].clone())
+}
+
+fn get_provided_value(field: &FieldInfo, name: &str, state: &GeneratorState) -> Option<String> {
+    if state.config.value_providers.is_empty()
+        || field.kind == FieldKind::Attribute
+        || get_field_struct(state.structs, &field.field_type.name).is_some()
+    {
+        return None;
+    }
+
+    let mut path = state.path.clone();
+    if field.kind != FieldKind::Text {
+        path.push(name.to_string());
+    }
+
+    let context = ValueContext {
+        element_name: name.to_string(),
+        type_name: field
+            .field_type
+            .facets
+            .as_ref()
+            .and_then(|x| x.type_name.clone())
+            .unwrap_or_default(),
+        path,
+    };
+
+    let providers = &state.config.value_providers;
+    providers.iter().find_map(|provider| provider(&context))
+}
+
+fn get_value(field: &FieldInfo, name: &str, state: &mut GeneratorState) -> Option<String> {
+    let facets = field.field_type.facets.as_ref();
+    if let Some(value) = facets.and_then(|x| x.fixed.as_ref()) {
+        return Option::from(value.clone());
+    }
+
+    if let Some(value) = get_provided_value(field, name, state) {
+        return Option::from(value);
+    }
+
+    if let Some(value) = get_pool_value(name, state) {
+        return Option::from(value);
+    }
+
+    if state.config.xml_lang && is_xml_lang(field) {
+        return Option::from(get_language_tag(state.config.locale).to_string());
+    }
+
+    if let Some(facets) = facets {
+        if let Some(value) = get_out_of_bounds_value(&field.field_type.name, name, facets, state) {
+            return Option::from(value);
+        }
+
+        let strategy = state.config.enumeration_strategy;
+        if let Some(value) = get_enumeration(facets, strategy, &mut state.rng) {
+            return Option::from(value);
+        }
+
+        if let Some(value) = get_pattern_string(facets, state) {
+            return Option::from(value);
+        }
+
+        let (mode, lowercase_hex) = (state.config.timezone, state.config.lowercase_hex);
+        if let Some(value) = get_builtin_string(facets, mode, lowercase_hex, &mut state.rng) {
+            return Option::from(value);
+        }
+
+        if let Some(value) = get_bounded_integer(&field.field_type.name, facets, state) {
+            return Option::from(value);
+        }
+
+        if let Some(value) = get_bounded_decimal(&field.field_type.name, facets, state) {
+            return Option::from(value);
+        }
+    }
+
+    let value = get_localised_string(field, state)
+        .or_else(|| get_hinted_string(field, state))
+        .or_else(|| get_string(&field.field_type.name, state))?;
+    Option::from(fit_text(value, field, facets, state))
+}
+
+const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+fn choose_derived_type<'a>(
+    structure: &'a StructInfo,
+    state: &mut GeneratorState<'a>,
+) -> (&'a StructInfo, Option<String>) {
+    if !state.config.xsi_type {
+        return (structure, None);
+    }
+
+    let mut derived = vec![];
+    for candidate in state.structs.iter() {
+        if candidate.base.as_ref() == Some(&structure.name) {
+            derived.push(candidate);
+        }
+    }
+
+    if derived.is_empty() || !state.rng.random_bool(0.5) {
+        return (structure, None);
+    }
+
+    let index = state.rng.random_range(0..derived.len());
+    (derived[index], derived[index].type_name.clone())
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+const CDATA_MARKUP_THRESHOLD: usize = 2;
+
+fn write_text(value: &str, config: &GeneratorConfig) -> String {
+    let markup = value.matches(['<', '&']).count();
+    if !config.cdata_heuristics || markup < CDATA_MARKUP_THRESHOLD {
+        return escape_text(value);
+    }
+
+    format!("<![CDATA[{}]]>", value.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+fn escape_attribute(value: &str) -> String {
+    escape_text(value)
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+enum XMLNode {
+    Element(XMLElement),
+    Comment(String),
+}
+
+enum XMLContent {
+    Empty,
+    Nodes(Vec<XMLNode>),
+    Text(String),
+}
+
+struct XMLElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    content: XMLContent,
+}
+
+impl XMLElement {
+    fn new(name: &str) -> Self {
+        XMLElement {
+            name: name.to_string(),
+            attributes: vec![],
+            content: XMLContent::Empty,
+        }
+    }
+
+    fn add_attribute(&mut self, name: &str, value: &str) {
+        self.attributes
+            .push((name.to_string(), escape_attribute(value)));
+    }
+
+    fn add_node(&mut self, node: XMLNode) -> Result<(), XMLGeneratorError> {
+        match &mut self.content {
+            XMLContent::Empty => self.content = XMLContent::Nodes(vec![node]),
+            XMLContent::Nodes(nodes) => nodes.push(node),
+            XMLContent::Text(_) => {
+                return Err(XMLGenerationError(format!(
+                    "Cannot add a child to `{}`, which already has text",
+                    self.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_child(&mut self, child: XMLElement) -> Result<(), XMLGeneratorError> {
+        self.add_node(XMLNode::Element(child))
+    }
+
+    fn add_comment(&mut self, comment: String) -> Result<(), XMLGeneratorError> {
+        self.add_node(XMLNode::Comment(comment))
+    }
+
+    fn add_text(&mut self, text: String) -> Result<(), XMLGeneratorError> {
+        if !matches!(self.content, XMLContent::Empty) {
+            return Err(XMLGenerationError(format!(
+                "Cannot add text to `{}`, which already has content",
+                self.name
+            )));
+        }
+
+        self.content = XMLContent::Text(text);
+        Ok(())
+    }
+
+    fn render(&self, output: &mut String, depth: usize) {
+        let indent = "\t".repeat(depth);
+        output.push_str(&indent);
+        output.push('<');
+        output.push_str(&self.name);
+        for (name, value) in self.attributes.iter() {
+            output.push_str(&format!(" {}=\"{}\"", name, value));
+        }
+
+        match &self.content {
+            XMLContent::Empty => output.push_str(" />\n"),
+            XMLContent::Text(text) => {
+                output.push_str(&format!(">{}</{}>\n", text, self.name));
+            }
+            XMLContent::Nodes(nodes) => {
+                output.push_str(">\n");
+                for node in nodes.iter() {
+                    match node {
+                        XMLNode::Element(child) => child.render(output, depth + 1),
+                        XMLNode::Comment(comment) => {
+                            output.push_str(&format!("{}\t<!-- {} -->\n", indent, comment));
+                        }
+                    }
+                }
+                output.push_str(&format!("{}</{}>\n", indent, self.name));
+            }
+        }
+    }
+}
+
+fn line_size(depth: usize, config: &GeneratorConfig) -> usize {
+    if config.pretty {
+        depth * config.indent.unwrap_or(1) + 1
+    } else {
+        0
+    }
+}
+
+fn reserve_bytes(
+    state: &mut GeneratorState,
+    size: usize,
+    name: &str,
+) -> Result<(), XMLGeneratorError> {
+    let bytes_used = state.bytes_used + size;
+    if let Some(max_bytes) = state.config.max_bytes
+        && bytes_used > max_bytes
+    {
+        state.budget_exceeded = true;
+        return Err(XMLGenerationError(format!(
+            "Required content of `{}` does not fit in the {} byte budget",
+            name, max_bytes
+        )));
+    }
+
+    state.bytes_used = bytes_used;
+    Ok(())
+}
+
+fn reserve_element(state: &mut GeneratorState) -> bool {
+    if let Some(max_total_elements) = state.config.max_total_elements
+        && state.elements_used >= max_total_elements
+    {
+        state.fallback(format!(
+            "Stopped at the budget of {} elements, later elements were left out",
+            max_total_elements
+        ));
+        return false;
+    }
+
+    state.elements_used += 1;
+    true
+}
+
+fn attribute_size(name: &str, value: &str) -> usize {
+    name.len() + escape_attribute(value).len() + 4
+}
+
+fn add_xsi_attribute(
+    element: &mut XMLElement,
+    name: &str,
+    value: &str,
+    state: &mut GeneratorState,
+) -> Result<(), XMLGeneratorError> {
+    let attribute = format!("xsi:{}", name);
+    let mut size = attribute_size(&attribute, value);
+    if !state.uses_xsi {
+        size += attribute_size("xmlns:xsi", XSI_NAMESPACE);
+    }
+
+    reserve_bytes(state, size, &attribute)?;
+    element.add_attribute(&attribute, value);
+    state.uses_xsi = true;
+    Ok(())
+}
+
+fn qualify(
+    name: &str,
+    namespace: &Option<XmlNamespace>,
+    state: &mut GeneratorState,
+) -> Result<String, XMLGeneratorError> {
+    let name = match state.config.name_transform {
+        Some(transform) => transform(name),
+        None => name.to_string(),
+    };
+
+    let Some(namespace) = namespace else {
+        return Ok(name);
+    };
+
+    let mut namespace = namespace.clone();
+    if let Some(prefix) = state.config.namespace_prefixes.get(&namespace.uri) {
+        namespace.prefix = prefix.clone();
+    }
+
+    if !state.namespaces.contains(&namespace) {
+        if namespace.prefix != "xml" {
+            let declaration = format!("xmlns:{}", namespace.prefix);
+            reserve_bytes(state, attribute_size(&declaration, &namespace.uri), &name)?;
+        }
+
+        state.namespaces.push(namespace.clone());
+    }
+
+    Ok(format!("{}:{}", namespace.prefix, name))
+}
+
+fn get_element(
+    field: &FieldInfo,
+    state: &mut GeneratorState,
+    depth: usize,
+) -> Result<Option<XMLElement>, XMLGeneratorError> {
+    if depth >= state.config.max_depth && field.field_type.min_occurrences == Some(0) {
+        state.warning(format!(
+            "Optional element `{}` left out beyond the maximum depth of {}",
+            field.xml_name, state.config.max_depth
+        ));
+        return Ok(None);
+    }
+
+    let structs = state.structs;
+    for structure in structs {
+        if structure.name == field.field_type.name {
+            let (structure, type_name) = choose_derived_type(structure, state);
+            let tag = qualify(&field.xml_name, &field.namespace, state)?;
+            let mut element = generate_element(structure, &field.xml_name, &tag, state, depth + 1)?;
+            if let Some(type_name) = type_name {
+                add_xsi_attribute(&mut element, "type", &type_name, state)?;
+            }
+
+            return Ok(Option::from(element));
+        }
+    }
+
+    state.fallback(format!(
+        "No generator for type `{}`, `{}` was left out",
+        field.field_type.name, field.xml_name
+    ));
+    Ok(None)
+}
+
+fn get_child(
+    field: &FieldInfo,
+    state: &mut GeneratorState,
+    depth: usize,
+) -> Result<Option<XMLElement>, XMLGeneratorError> {
+    if let Some(value) = get_value(field, &field.xml_name, state) {
+        let value = write_text(&value, state.config);
+        let tag = qualify(&field.xml_name, &field.namespace, state)?;
+        let size = line_size(depth + 1, state.config) + 2 * tag.len() + 5 + value.len();
+        reserve_bytes(state, size, &field.xml_name)?;
+
+        let mut child = XMLElement::new(&tag);
+        child.add_text(value)?;
+        return Ok(Option::from(child));
+    }
+
+    get_element(field, state, depth)
+}
+
+fn is_excluded(field: &FieldInfo, state: &mut GeneratorState) -> bool {
+    if !state.config.exclude.contains(&field.xml_name) {
+        return false;
+    }
+
+    if field.field_type.min_occurrences != Some(0) {
+        state.fallback(format!(
+            "Required element `{}` is excluded, the output will not be valid",
+            field.xml_name
+        ));
+    }
+
+    true
+}
+
+fn add_attribute(
+    element: &mut XMLElement,
+    field: &FieldInfo,
+    state: &mut GeneratorState,
+) -> Result<(), XMLGeneratorError> {
+    let forced = state.config.xml_lang && is_xml_lang(field);
+    let optional = field.field_type.min_occurrences == Some(0) && !forced;
+    if state.config.attribute_mode == AttributeMode::None && !forced {
+        if !optional {
+            state.fallback(format!(
+                "Required attribute `{}` is left out, the output will not be valid",
+                field.xml_name
+            ));
+        }
+
+        return Ok(());
+    }
+
+    if optional && state.config.attribute_mode == AttributeMode::RequiredOnly {
+        return Ok(());
+    }
+
+    let probability = state.config.optional_probability.unwrap_or(0.5);
+    if optional && !state.rng.random_bool(probability) {
+        return Ok(());
+    }
+
+    if let Some(value) = get_value(field, &field.xml_name, state) {
+        let reserved = qualify(&field.xml_name, &field.namespace, state).and_then(|name| {
+            let size = attribute_size(&name, &value);
+            reserve_bytes(state, size, &field.xml_name).map(|_| name)
+        });
+        match reserved {
+            Ok(name) => {
+                element.add_attribute(&name, &value);
+                state.cover(&field.xml_name, 1);
+            }
+            Err(_) if optional => state.budget_exceeded = false,
+            Err(err) => return Err(err),
+        }
+    } else if optional {
+        state.fallback(format!(
+            "No generator for type `{}`, `{}` was left out",
+            field.field_type.name, field.xml_name
+        ));
+    } else {
+        return Err(XMLGenerationError(format!(
+            "No generator for type `{}` of required attribute `{}`",
+            field.field_type.name, field.xml_name
+        )));
+    }
+
+    Ok(())
+}
+
+fn get_annotation(field: &FieldInfo, substitute: Option<&FieldInfo>, name: &str) -> String {
+    match substitute {
+        Some(branch) => format!("element: {}, choice branch: {}", name, branch.xml_name),
+        None if field.field_type.min_occurrences == Some(0) => {
+            format!("element: {}, optional element included", field.xml_name)
+        }
+        None => String::new(),
+    }
+}
+
+fn annotate(
+    element: &mut XMLElement,
+    annotation: &str,
+    name: &str,
+    state: &mut GeneratorState,
+    depth: usize,
+) -> Result<(), XMLGeneratorError> {
+    if !state.config.annotate || annotation.is_empty() {
+        return Ok(());
+    }
+
+    let comment = sanitise_comment(annotation);
+    let size = line_size(depth + 1, state.config) + comment.len() + 9;
+    if reserve_bytes(state, size, name).is_err() {
+        state.budget_exceeded = false;
+        return Ok(());
+    }
+
+    element.add_comment(comment)
+}
+
+fn sanitise_comment(text: &str) -> String {
+    let mut comment = text.to_string();
+    while comment.contains("--") {
+        comment = comment.replace("--", "- -");
+    }
+
+    if comment.ends_with('-') {
+        comment.push(' ');
+    }
+
+    comment
+}
+
+fn get_closing_tag(content: &str) -> Option<String> {
+    if content.starts_with("</") || content.starts_with("<!") {
+        return None;
+    }
+
+    let tag_end = content.find('>')?;
+    let tag = &content[1..tag_end];
+    let text = &content[tag_end + 1..];
+    if tag.ends_with('/') || text.is_empty() {
+        return None;
+    }
+
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let closing_tag = format!("</{}>", &tag[..name_end]);
+    match text.ends_with(&closing_tag) {
+        true => None,
+        false => Option::from(closing_tag),
+    }
+}
+
+fn apply_layout(xml: String, config: &GeneratorConfig) -> String {
+    if config.pretty && config.indent.is_none() {
+        return xml;
+    }
+
+    let mut output = String::new();
+    let mut closing_tag: Option<String> = None;
+    for line in xml.lines() {
+        let content = line.trim_start_matches('\t');
+        let text_line = closing_tag.is_some() || !content.starts_with('<');
+        match &closing_tag {
+            Some(tag) if line.ends_with(tag.as_str()) => closing_tag = None,
+            Some(_) => {}
+            None if !text_line => closing_tag = get_closing_tag(content),
+            None => {}
+        }
+
+        if config.pretty {
+            if text_line {
+                output.push_str(line);
+            } else {
+                let depth = line.len() - content.len();
+                output.push_str(&" ".repeat(depth * config.indent.unwrap_or(1)));
+                output.push_str(content);
+            }
+            output.push('\n');
+        } else if text_line {
+            output.push('\n');
+            output.push_str(line);
+        } else {
+            output.push_str(content);
+        }
+    }
+
+    output
+}
+
+fn get_max_repetitions(field_type: &FieldType, config: &GeneratorConfig) -> u64 {
+    let Some(min_occurrences) = field_type.min_occurrences else {
+        return 1;
+    };
+
+    match field_type.max_occurrences {
+        Some(x) => x,
+        None => min_occurrences.max(config.max_repeats),
+    }
+}
+
+fn report_capped(field_type: &FieldType, particle: &str, state: &mut GeneratorState) {
+    if field_type.min_occurrences.is_none() || field_type.max_occurrences.is_some() {
+        return;
+    }
+
+    let message = format!(
+        "Unbounded {} capped at {} repetitions",
+        particle,
+        get_max_repetitions(field_type, state.config)
+    );
+    match state.config.strict {
+        true => state.fallback(message),
+        false => state.warning(message),
+    }
+}
+
+fn get_repetitions(field_type: &FieldType, state: &mut GeneratorState) -> u64 {
+    let Some(min_occurrences) = field_type.min_occurrences else {
+        return 1;
+    };
+
+    let max_occurrences = get_max_repetitions(field_type, state.config);
+    let mut min_occurrences = min_occurrences;
+    if min_occurrences == 0
+        && max_occurrences > 0
+        && let Some(probability) = state.config.optional_probability
+    {
+        if !state.rng.random_bool(probability) {
+            return 0;
+        }
+
+        min_occurrences = 1;
+    }
+
+    if min_occurrences >= max_occurrences {
+        return max_occurrences;
+    }
+
+    (min_occurrences..=max_occurrences).fake_with_rng(&mut state.rng)
+}
+
+fn choose_branch(
+    branches: &[(String, FieldKind, FieldType)],
+    name: &str,
+    state: &mut GeneratorState,
+) -> usize {
+    let weights = &state.config.choice_weights;
+    let keys: Vec<(String, String)> = branches
+        .iter()
+        .map(|(branch, _, _)| (name.to_string(), branch.clone()))
+        .collect();
+
+    if !keys.iter().any(|x| weights.contains_key(x)) {
+        return state.rng.random_range(0..branches.len());
+    }
+
+    let weights = keys.iter().map(|x| weights.get(x).copied().unwrap_or(1.0));
+    match WeightedIndex::new(weights) {
+        Ok(distribution) => state.rng.sample(distribution),
+        Err(err) => {
+            state.fallback(format!(
+                "Invalid choice weights in `{}`, choosing uniformly: {}",
+                name, err
+            ));
+            state.rng.random_range(0..branches.len())
+        }
+    }
+}
+
+fn get_substitute(field: &FieldInfo, name: &str, state: &mut GeneratorState) -> Option<FieldInfo> {
+    let substitutes = field.substitutes.as_ref()?;
+    let (xml_name, kind, branch_type) = &substitutes[choose_branch(substitutes, name, state)];
+    let field_type = &field.field_type;
+    let (min_occurrences, max_occurrences) = match kind {
+        FieldKind::Group => (Some(1), Some(1)),
+        _ => (field_type.min_occurrences, field_type.max_occurrences),
+    };
+
+    Option::from(FieldInfo {
+        name: field.name.clone(),
+        xml_name: xml_name.clone(),
+        kind: *kind,
+        field_type: FieldType {
+            min_occurrences,
+            max_occurrences,
+            ..branch_type.clone()
+        },
+        attributes: field.attributes.clone(),
+        namespace: field.namespace.clone(),
+        substitutes: None,
+    })
+}
+
+fn add_children(
+    element: &mut XMLElement,
+    field: &FieldInfo,
+    name: &str,
+    state: &mut GeneratorState,
+    depth: usize,
+) -> Result<(), XMLGeneratorError> {
+    let field_type = &field.field_type;
+    report_capped(field_type, &format!("element `{}`", field.xml_name), state);
+
+    let min_occurrences = field_type.min_occurrences.unwrap_or(1);
+    if field.substitutes.as_ref().is_some_and(|x| x.is_empty()) {
+        if min_occurrences == 0 {
+            return Ok(());
+        }
+
+        return Err(InvalidInputError(format!(
+            "Abstract element `{}` has no substitution group members",
+            field.xml_name
+        )));
+    }
+
+    let mut repetitions = get_repetitions(field_type, state);
+    if min_occurrences > 0
+        && state.violate(|| format!("Left out required element `{}`", field.xml_name))
+    {
+        repetitions = min_occurrences - 1;
+    }
+
+    for index in 0..repetitions {
+        if !reserve_element(state) {
+            break;
+        }
+
+        let budget = state.budget();
+        let substitute = get_substitute(field, name, state);
+        if let Some(group) = substitute.as_ref().filter(|x| x.kind == FieldKind::Group) {
+            state.elements_used -= 1;
+            state.cover(&group.xml_name, 1);
+            let annotation = get_annotation(field, Some(group), name);
+            annotate(element, &annotation, name, state, depth)?;
+            add_group(element, group, &field.xml_name, state, depth)?;
+            continue;
+        }
+
+        let child = match get_child(substitute.as_ref().unwrap_or(field), state, depth) {
+            Ok(child) => child,
+            Err(_) if state.budget_exceeded && index >= min_occurrences => {
+                state.restore(budget);
+                state.warning(format!(
+                    "Stopped adding `{}` at the byte budget",
+                    field.xml_name
+                ));
+                break;
+            }
+            Err(err) => return Err(err),
+        };
+
+        match child {
+            Some(child) => {
+                let annotation = get_annotation(field, substitute.as_ref(), name);
+                annotate(element, &annotation, name, state, depth)?;
+                element.add_child(child)?;
+                state.cover(&substitute.as_ref().unwrap_or(field).xml_name, 1);
+            }
+            None => state.elements_used -= 1,
+        }
+    }
+
+    Ok(())
+}
+
+fn make_wildcard_element(
+    wildcard: &Wildcard,
+    state: &mut GeneratorState,
+    depth: usize,
+) -> Result<XMLElement, XMLGeneratorError> {
+    let name = match wildcard.namespace {
+        Some(_) => "wc:wildcard",
+        None => "wildcard",
+    };
+
+    let value = get_string("String", state).unwrap_or_default();
+    let size = line_size(depth + 1, state.config) + 2 * name.len() + 5 + value.len();
+    reserve_bytes(state, size, name)?;
+
+    let mut element = XMLElement::new(name);
+    if let Some(namespace) = &wildcard.namespace {
+        reserve_bytes(state, attribute_size("xmlns:wc", namespace), name)?;
+        element.add_attribute("xmlns:wc", namespace);
+    }
+
+    element.add_text(value)?;
+    Ok(element)
+}
+
+fn add_wildcard(
+    element: &mut XMLElement,
+    wildcard: &Wildcard,
+    name: &str,
+    state: &mut GeneratorState,
+    depth: usize,
+) -> Result<(), XMLGeneratorError> {
+    let min_occurrences = wildcard.field_type.min_occurrences.unwrap_or(1);
+    let skip = wildcard.process_contents == ProcessContentsType::Skip;
+    if skip || state.config.skip_wildcards {
+        if min_occurrences > 0 {
+            state.fallback(format!("Required wildcard in `{}` was left out", name));
+        }
+
+        return Ok(());
+    }
+
+    if wildcard.process_contents == ProcessContentsType::Strict {
+        state.fallback(format!(
+            "Strict wildcard in `{}` filled with an undeclared element",
+            name
+        ));
+    }
+
+    report_capped(
+        &wildcard.field_type,
+        &format!("wildcard in `{}`", name),
+        state,
+    );
+    for index in 0..get_repetitions(&wildcard.field_type, state) {
+        if !reserve_element(state) {
+            break;
+        }
+
+        let budget = state.budget();
+        let child = match make_wildcard_element(wildcard, state, depth) {
+            Ok(child) => child,
+            Err(_) if state.budget_exceeded && index >= min_occurrences => {
+                state.restore(budget);
+                break;
+            }
+            Err(err) => return Err(err),
+        };
+
+        element.add_child(child)?;
+    }
+
+    Ok(())
+}
+
+fn follows_field(wildcard: &Wildcard, field: Option<&FieldInfo>) -> bool {
+    let (Some(after), Some(field)) = (&wildcard.after, field) else {
+        return wildcard.after.is_none() && field.is_none();
+    };
+
+    match &field.substitutes {
+        Some(branches) => branches.iter().any(|(x, _, _)| x == after),
+        None => field.kind == FieldKind::Element && field.xml_name == *after,
+    }
+}
+
+fn add_placed_wildcards(
+    element: &mut XMLElement,
+    field: Option<&FieldInfo>,
+    name: &str,
+    state: &mut GeneratorState,
+    depth: usize,
+) -> Result<(), XMLGeneratorError> {
+    let Some((wildcards, _)) = state.placed_wildcards.last() else {
+        return Ok(());
+    };
+
+    let wildcards = *wildcards;
+    for (index, wildcard) in wildcards.iter().enumerate() {
+        if wildcard.open_content.is_some() || !follows_field(wildcard, field) {
+            continue;
+        }
+
+        if let Some((_, placed)) = state.placed_wildcards.last_mut() {
+            placed[index] = true;
+        }
+        add_wildcard(element, wildcard, name, state, depth)?;
+    }
+
+    Ok(())
+}
+
+fn add_wildcards(
+    element: &mut XMLElement,
+    name: &str,
+    state: &mut GeneratorState,
+    depth: usize,
+) -> Result<(), XMLGeneratorError> {
+    let Some((wildcards, placed)) = state.placed_wildcards.last().cloned() else {
+        return Ok(());
+    };
+
+    for (wildcard, placed) in wildcards.iter().zip(placed) {
+        match wildcard.open_content {
+            None if placed => {}
+            None => add_wildcard(element, wildcard, name, state, depth)?,
+            Some(OpenContentModeType::Suffix) if state.config.open_content => {
+                add_wildcard(element, wildcard, name, state, depth)?
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn get_interleave_position<'a>(
+    structure: &StructInfo,
+    state: &mut GeneratorState<'a>,
+) -> Option<(usize, &'a Wildcard)> {
+    if !state.config.open_content {
+        return None;
+    }
+
+    let wildcards = state.wildcards;
+    let wildcard = wildcards
+        .get(&structure.name)?
+        .iter()
+        .find(|x| x.open_content == Some(OpenContentModeType::Interleave))?;
+
+    let position = state.rng.random_range(0..=structure.fields.len());
+    Option::from((position, wildcard))
+}
+
+fn reorder_elements(fields: &mut [&FieldInfo], state: &mut GeneratorState) {
+    let mut positions = vec![];
+    let mut elements = vec![];
+    for (index, field) in fields.iter().enumerate() {
+        if field.kind == FieldKind::Element {
+            positions.push(index);
+            elements.push(*field);
+        }
+    }
+
+    if state.config.sort_all_groups {
+        elements.sort_by(|a, b| a.xml_name.cmp(&b.xml_name));
+    } else {
+        elements.shuffle(&mut state.rng);
+    }
+
+    for (index, field) in positions.into_iter().zip(elements) {
+        fields[index] = field;
+    }
+}
+
+fn add_group(
+    element: &mut XMLElement,
+    field: &FieldInfo,
+    name: &str,
+    state: &mut GeneratorState,
+    depth: usize,
+) -> Result<(), XMLGeneratorError> {
+    let Some(group) = get_field_struct(state.structs, &field.field_type.name) else {
+        state.fallback(format!(
+            "No generator for group `{}` in `{}`, it was left out",
+            field.field_type.name, name
+        ));
+        return Ok(());
+    };
+
+    report_capped(&field.field_type, &format!("group in `{}`", name), state);
+    let min_occurrences = field.field_type.min_occurrences.unwrap_or(1);
+    for index in 0..get_repetitions(&field.field_type, state) {
+        match add_fields(element, group, name, None, state, depth) {
+            Ok(()) => {}
+            Err(_) if state.budget_exceeded && index >= min_occurrences => {
+                // Children added before the failure stay in the element, so
+                // their bytes stay reserved.
+                state.budget_exceeded = false;
+                state.warning(format!(
+                    "Stopped repeating a group in `{}` at the byte budget",
+                    name
+                ));
+                break;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(())
+}
+
+fn add_fields(
+    element: &mut XMLElement,
+    structure: &StructInfo,
+    name: &str,
+    interleave: Option<(usize, &Wildcard)>,
+    state: &mut GeneratorState,
+    depth: usize,
+) -> Result<(), XMLGeneratorError> {
+    let mut fields: Vec<&FieldInfo> = structure.fields.iter().collect();
+    if structure.unordered {
+        reorder_elements(&mut fields, state);
+    }
+
+    for (index, field) in fields.iter().enumerate() {
+        if let Some((position, wildcard)) = interleave
+            && position == index
+        {
+            add_wildcard(element, wildcard, name, state, depth)?;
+        }
+
+        if is_excluded(field, state) {
+            add_placed_wildcards(element, Some(field), name, state, depth)?;
+            continue;
+        }
+
+        if let Some(facets) = &field.field_type.facets {
+            let target = match field.kind {
+                FieldKind::Text => name,
+                _ => field.xml_name.as_str(),
+            };
+            for ignored in facets.ignored.iter() {
+                state.fallback(format!(
+                    "Unsupported {} of `{}` was ignored",
+                    ignored, target
+                ));
+            }
+        }
+
+        match &field.substitutes {
+            Some(branches) => branches.iter().for_each(|(x, _, _)| state.cover(x, 0)),
+            None if matches!(field.kind, FieldKind::Element | FieldKind::Attribute) => {
+                state.cover(&field.xml_name, 0)
+            }
+            None => {}
+        }
+
+        match field.kind {
+            FieldKind::Element => add_children(element, field, name, state, depth)?,
+            FieldKind::Attribute => add_attribute(element, field, state)?,
+            FieldKind::Group if field.substitutes.is_some() => {
+                add_children(element, field, name, state, depth)?
+            }
+            FieldKind::Group => add_group(element, field, name, state, depth)?,
+            FieldKind::Text => {
+                if let Some(value) = get_value(field, name, state) {
+                    let value = write_text(&value, state.config);
+                    reserve_bytes(state, value.len(), name)?;
+                    element.add_text(value)?;
+                }
+            }
+        }
+
+        add_placed_wildcards(element, Some(field), name, state, depth)?;
+    }
+
+    if let Some((position, wildcard)) = interleave
+        && position >= fields.len()
+    {
+        add_wildcard(element, wildcard, name, state, depth)?;
+    }
+
+    Ok(())
+}
+
+fn generate_element(
+    root: &StructInfo,
+    name: &str,
+    tag: &str,
+    state: &mut GeneratorState,
+    depth: usize,
+) -> Result<XMLElement, XMLGeneratorError> {
+    let size = 2 * line_size(depth, state.config) + 2 * tag.len() + 5;
+    reserve_bytes(state, size, name)?;
+    let mut element = XMLElement::new(tag);
+
+    let interleave = get_interleave_position(root, state);
+    let wildcards = state.wildcards;
+    let wildcards = wildcards.get(&root.name).map_or(&[][..], |x| x.as_slice());
+    state.path.push(name.to_string());
+    let definition = root.definition.clone().unwrap_or(name.to_string());
+    state.definitions.push(definition);
+    state
+        .placed_wildcards
+        .push((wildcards, vec![false; wildcards.len()]));
+    let result = add_placed_wildcards(&mut element, None, name, state, depth)
+        .and_then(|_| add_fields(&mut element, root, name, interleave, state, depth))
+        .and_then(|_| add_wildcards(&mut element, name, state, depth));
+    state.placed_wildcards.pop();
+    state.definitions.pop();
+    state.path.pop();
+
+    result?;
+    Ok(element)
+}
+
+fn estimate_branch_count(
+    kind: FieldKind,
+    branch_type: &FieldType,
+    structs: &[StructInfo],
+    config: &GeneratorConfig,
+    depth: usize,
+) -> usize {
+    match get_field_struct(structs, &branch_type.name) {
+        Some(group) if kind == FieldKind::Group => {
+            estimate_element_count(group, structs, config, depth) - 1
+        }
+        Some(child) => estimate_element_count(child, structs, config, depth + 1),
+        None => 1,
+    }
+}
+
+fn estimate_element_count(
+    structure: &StructInfo,
+    structs: &[StructInfo],
+    config: &GeneratorConfig,
+    depth: usize,
+) -> usize {
+    let mut count: usize = 1;
+
+    for field in structure.fields.iter() {
+        if let Some(substitutes) = &field.substitutes {
+            let branches = substitutes.iter();
+            let branch_count = branches
+                .map(|(_, kind, branch_type)| {
+                    estimate_branch_count(*kind, branch_type, structs, config, depth)
+                })
+                .max()
+                .unwrap_or(0);
+
+            let repetitions = get_max_repetitions(&field.field_type, config) as usize;
+            count = count.saturating_add(repetitions.saturating_mul(branch_count));
+            continue;
+        }
+
+        if field.kind == FieldKind::Group {
+            let group_count = match get_field_struct(structs, &field.field_type.name) {
+                Some(group) => estimate_element_count(group, structs, config, depth) - 1,
+                None => 0,
+            };
+
+            let repetitions = get_max_repetitions(&field.field_type, config) as usize;
+            count = count.saturating_add(repetitions.saturating_mul(group_count));
+            continue;
+        }
+
+        if field.kind != FieldKind::Element || config.exclude.contains(&field.xml_name) {
+            continue;
+        }
+
+        let beyond_depth = depth >= config.max_depth && field.field_type.min_occurrences == Some(0);
+        let child_count = match get_field_struct(structs, &field.field_type.name) {
+            Some(_) if beyond_depth => 0,
+            Some(child) => estimate_element_count(child, structs, config, depth + 1),
+            None => 1,
+        };
+
+        let repetitions = get_max_repetitions(&field.field_type, config) as usize;
+        count = count.saturating_add(repetitions.saturating_mul(child_count));
+    }
+
+    count
+}
+
+fn get_structs(data_types: &DataTypes) -> Result<Vec<StructInfo>, XMLGeneratorError> {
+    let data = render(data_types);
+
+    let (_type_aliases, mut structs) = get_data(&data)?;
+    apply_substitutes(&mut structs, &data, data_types);
+    apply_choices(&mut structs, &data, data_types);
+    apply_occurrences(&mut structs, &get_occurrences(data_types));
+    apply_derivations(&mut structs, data_types);
+    apply_element_names(&mut structs, data_types);
+    apply_all_groups(&mut structs, data_types);
+    apply_group_fields(&mut structs, data_types);
+
+    Ok(structs)
+}
+
+fn get_root_name(root: &StructInfo) -> &str {
+    root.element_name.as_ref().unwrap_or(&root.name)
+}
+
+const XML_DECLARATION: &str = r#"<?xml version="1.1" encoding="UTF-8"?>"#;
+
+fn generate_root(
+    root: &StructInfo,
+    state: &mut GeneratorState,
+) -> Result<XMLElement, XMLGeneratorError> {
+    state.bytes_used = XML_DECLARATION.len() + line_size(0, state.config);
+    state.elements_used = 1;
+    let name = get_root_name(root);
+    let tag = qualify(name, &root.namespace, state)?;
+    let mut language = None;
+    if state.config.xml_lang && !root.fields.iter().any(is_xml_lang) {
+        if root.allows_xml_lang {
+            let value = get_language_tag(state.config.locale);
+            reserve_bytes(state, attribute_size("xml:lang", value), name)?;
+            language = Some(value);
+        } else {
+            state.fallback(format!(
+                "Root element `{}` does not allow `xml:lang`, it was left out",
+                name
+            ));
+        }
+    }
+
+    let mut root_element = generate_element(root, name, &tag, state, 0)?;
+    if let Some(language) = language {
+        root_element.add_attribute("xml:lang", language);
+    }
+
+    if state.uses_xsi {
+        root_element.add_attribute("xmlns:xsi", XSI_NAMESPACE);
+    }
+
+    for namespace in state.namespaces.iter() {
+        if namespace.prefix != "xml" {
+            root_element.add_attribute(&format!("xmlns:{}", namespace.prefix), &namespace.uri);
+        }
+    }
+
+    Ok(root_element)
+}
+
+fn generate_document(
+    root: &StructInfo,
+    structs: &[StructInfo],
+    wildcards: &Wildcards,
+    config: &GeneratorConfig,
+) -> Result<(String, GenerationReport), XMLGeneratorError> {
+    config.validate()?;
+
+    let mut state = GeneratorState::new(structs, wildcards, config);
+    let mut root_element = generate_root(root, &mut state)?;
+    if config.mode == GenerationMode::Invalid && state.violation.is_none() {
+        state = GeneratorState::new(structs, wildcards, config);
+        state.force_violation = true;
+        root_element = generate_root(root, &mut state)?;
+        if state.violation.is_none() {
+            return Err(XMLGenerationError(
+                "No required element or bounded value to violate".to_string(),
+            ));
+        }
+    }
+
+    if config.strict
+        && let Some(workaround) = state.workarounds.first()
+    {
+        return Err(XMLGenerationError(format!(
+            "Strict mode does not allow fallbacks: {}",
+            workaround
+        )));
+    }
+
+    let mut xml = format!("{}\n", XML_DECLARATION);
+    root_element.render(&mut xml, 0);
+    Ok((apply_layout(xml, config), state.report()))
+}
+
+fn generate_xml_data(
+    schemas: &Schemas,
+    data_types: &DataTypes,
+    config: &GeneratorConfig,
+) -> Result<(GeneratedDocument, GenerationReport), XMLGeneratorError> {
+    let mut structs = get_structs(data_types)?;
+    apply_namespaces(&mut structs, schemas, data_types);
+    apply_restrictions(&mut structs, schemas, data_types);
+
+    let root = find_root(&structs)?;
+    let wildcards = get_wildcards(schemas, data_types);
+    let (xml, report) = generate_document(root, &structs, &wildcards, config)?;
+
+    let document = GeneratedDocument {
+        root_name: get_root_name(root).to_string(),
+        xml,
+    };
+    Ok((document, report))
+}
+
+fn generate_all_roots_data(
+    schemas: &Schemas,
+    data_types: &DataTypes,
+    config: &GeneratorConfig,
+) -> Result<Vec<(String, String)>, XMLGeneratorError> {
+    let mut structs = get_structs(data_types)?;
+    apply_namespaces(&mut structs, schemas, data_types);
+    apply_restrictions(&mut structs, schemas, data_types);
+
+    let roots = find_roots(&structs);
+    if roots.is_empty() {
+        return Err(InvalidInputError(
+            "No independent structs found".to_string(),
+        ));
+    }
+
+    let wildcards = get_wildcards(schemas, data_types);
+    let mut documents = vec![];
+    for root in roots {
+        let (xml, _) = generate_document(root, &structs, &wildcards, config)?;
+        documents.push((get_root_name(root).to_string(), xml));
+    }
+
+    Ok(documents)
+}
+
+fn make_attribute_group(group: &QName) -> AttributeGroupType {
+    AttributeGroupType {
+        id: None,
+        name: None,
+        ref_: Some(group.clone()),
+        content: vec![],
+    }
+}
+
+fn add_default_attributes_to_element(element: &mut ElementType, group: &QName) {
+    for content in element.content.iter_mut() {
+        if let ElementTypeContent::ComplexType(x) = content {
+            add_default_attributes(x, group);
+        }
+    }
+}
+
+fn add_default_attributes_to_group(content: &mut GroupType, group: &QName) {
+    for content in content.content.iter_mut() {
+        match content {
+            GroupTypeContent::Element(x) => add_default_attributes_to_element(x, group),
+            GroupTypeContent::Group(x)
+            | GroupTypeContent::All(x)
+            | GroupTypeContent::Choice(x)
+            | GroupTypeContent::Sequence(x) => add_default_attributes_to_group(x, group),
+            _ => {}
+        }
+    }
+}
+
+fn add_default_attributes_to_derivation(
+    content: &mut ComplexContentContent,
+    group: &QName,
+    apply: bool,
+) {
+    match content {
+        ComplexContentContent::Extension(extension) => {
+            for x in extension.content.iter_mut() {
+                if let ExtensionTypeContent::Group(x)
+                | ExtensionTypeContent::All(x)
+                | ExtensionTypeContent::Choice(x)
+                | ExtensionTypeContent::Sequence(x) = x
+                {
+                    add_default_attributes_to_group(x, group);
+                }
+            }
+
+            if apply {
+                let attribute_group = make_attribute_group(group);
+                extension
+                    .content
+                    .push(ExtensionTypeContent::AttributeGroup(attribute_group));
+            }
+        }
+        ComplexContentContent::Restriction(restriction) => {
+            for x in restriction.content.iter_mut() {
+                if let RestrictionTypeContent::Group(x)
+                | RestrictionTypeContent::All(x)
+                | RestrictionTypeContent::Choice(x)
+                | RestrictionTypeContent::Sequence(x) = x
+                {
+                    add_default_attributes_to_group(x, group);
+                }
+            }
+
+            if apply {
+                let attribute_group = make_attribute_group(group);
+                restriction
+                    .content
+                    .push(RestrictionTypeContent::AttributeGroup(attribute_group));
+            }
+        }
+        ComplexContentContent::Annotation(_) => {}
+    }
+}
+
+fn add_default_attributes(complex: &mut ComplexBaseType, group: &QName) {
+    let apply = complex.default_attributes_apply;
+    let mut derived = false;
+    for content in complex.content.iter_mut() {
+        match content {
+            ComplexBaseTypeContent::Group(x)
+            | ComplexBaseTypeContent::All(x)
+            | ComplexBaseTypeContent::Choice(x)
+            | ComplexBaseTypeContent::Sequence(x) => add_default_attributes_to_group(x, group),
+            ComplexBaseTypeContent::ComplexContent(x) => {
+                derived = true;
+                for x in x.content.iter_mut() {
+                    add_default_attributes_to_derivation(x, group, apply);
+                }
+            }
+            ComplexBaseTypeContent::SimpleContent(x) => {
+                derived = true;
+                for x in x.content.iter_mut() {
+                    let attribute_group = make_attribute_group(group);
+                    match x {
+                        SimpleContentContent::Extension(x) if apply => x
+                            .content
+                            .push(ExtensionTypeContent::AttributeGroup(attribute_group)),
+                        SimpleContentContent::Restriction(x) if apply => x
+                            .content
+                            .push(RestrictionTypeContent::AttributeGroup(attribute_group)),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if apply && !derived {
+        let attribute_group = make_attribute_group(group);
+        complex
+            .content
+            .push(ComplexBaseTypeContent::AttributeGroup(attribute_group));
+    }
+}
+
+fn apply_default_attributes(mut schemas: Schemas) -> Schemas {
+    for (_, schema) in schemas.schemas_mut() {
+        let Some(group) = schema.default_attributes.clone() else {
+            continue;
+        };
+
+        for content in schema.content.iter_mut() {
+            match content {
+                SchemaContent::Element(x) => add_default_attributes_to_element(x, &group),
+                SchemaContent::ComplexType(x) => add_default_attributes(x, &group),
+                SchemaContent::Group(x) => add_default_attributes_to_group(x, &group),
+                _ => {}
+            }
+        }
+    }
+
+    schemas
+}
+
+fn apply_default_alternative(element: &mut ElementType) {
+    let mut default = None;
+    for content in element.content.iter() {
+        if let ElementTypeContent::Alternative(x) = content
+            && x.test.is_none()
+        {
+            default = Option::from(x.clone());
+        }
+    }
+
+    element
+        .content
+        .retain(|x| !matches!(x, ElementTypeContent::Alternative(_)));
+
+    let Some(default) = default else {
+        return;
+    };
+
+    let mut content = None;
+    for x in default.content {
+        match x {
+            AltTypeContent::SimpleType(x) => content = Some(ElementTypeContent::SimpleType(x)),
+            AltTypeContent::ComplexType(x) => content = Some(ElementTypeContent::ComplexType(x)),
+            AltTypeContent::Annotation(_) => {}
+        }
+    }
+
+    if default.type_.is_none() && content.is_none() {
+        return;
+    }
+
+    element.content.retain(|x| {
+        !matches!(
+            x,
+            ElementTypeContent::SimpleType(_) | ElementTypeContent::ComplexType(_)
+        )
+    });
+    element.type_ = default.type_;
+    element.content.extend(content);
+}
+
+fn apply_alternatives_to_element(element: &mut ElementType) {
+    apply_default_alternative(element);
+    for content in element.content.iter_mut() {
+        if let ElementTypeContent::ComplexType(x) = content {
+            apply_alternatives_to_complex(x);
+        }
+    }
+}
+
+fn apply_alternatives_to_group(group: &mut GroupType) {
+    for content in group.content.iter_mut() {
+        match content {
+            GroupTypeContent::Element(x) => apply_alternatives_to_element(x),
+            GroupTypeContent::Group(x)
+            | GroupTypeContent::All(x)
+            | GroupTypeContent::Choice(x)
+            | GroupTypeContent::Sequence(x) => apply_alternatives_to_group(x),
+            _ => {}
+        }
+    }
+}
+
+fn apply_alternatives_to_complex(complex: &mut ComplexBaseType) {
+    for content in complex.content.iter_mut() {
+        match content {
+            ComplexBaseTypeContent::Group(x)
+            | ComplexBaseTypeContent::All(x)
+            | ComplexBaseTypeContent::Choice(x)
+            | ComplexBaseTypeContent::Sequence(x) => apply_alternatives_to_group(x),
+            ComplexBaseTypeContent::ComplexContent(x) => {
+                for x in x.content.iter_mut() {
+                    match x {
+                        ComplexContentContent::Extension(x) => {
+                            for x in x.content.iter_mut() {
+                                if let ExtensionTypeContent::Group(x)
+                                | ExtensionTypeContent::All(x)
+                                | ExtensionTypeContent::Choice(x)
+                                | ExtensionTypeContent::Sequence(x) = x
+                                {
+                                    apply_alternatives_to_group(x);
+                                }
+                            }
+                        }
+                        ComplexContentContent::Restriction(x) => {
+                            for x in x.content.iter_mut() {
+                                if let RestrictionTypeContent::Group(x)
+                                | RestrictionTypeContent::All(x)
+                                | RestrictionTypeContent::Choice(x)
+                                | RestrictionTypeContent::Sequence(x) = x
+                                {
+                                    apply_alternatives_to_group(x);
+                                }
+                            }
+                        }
+                        ComplexContentContent::Annotation(_) => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn apply_alternatives(mut schemas: Schemas) -> Schemas {
+    for (_, schema) in schemas.schemas_mut() {
+        for content in schema.content.iter_mut() {
+            match content {
+                SchemaContent::Element(x) => apply_alternatives_to_element(x),
+                SchemaContent::ComplexType(x) => apply_alternatives_to_complex(x),
+                SchemaContent::Group(x) => apply_alternatives_to_group(x),
+                _ => {}
+            }
+        }
+    }
+
+    schemas
+}
+
+fn apply_namespace_prefixes(mut schemas: Schemas) -> Schemas {
+    let mut visitor = PrefixVisitor::default();
+    walk_schemas(&mut visitor, &schemas);
+
+    for (prefix, namespace) in visitor.bindings {
+        let namespace = Some(namespace);
+        if schemas.resolve_namespace(&namespace).is_some() {
+            schemas.get_or_create_namespace_info_mut(Some(prefix), namespace);
+        }
+    }
+
+    schemas
+}
+
+fn prepare_schemas(schemas: Schemas) -> Schemas {
+    apply_namespace_prefixes(apply_alternatives(apply_default_attributes(schemas)))
+}
+
+const XML_NAMESPACE_SCHEMA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           targetNamespace="http://www.w3.org/XML/1998/namespace">
+  <xs:attribute name="lang" type="xs:language"/>
+</xs:schema>"#;
+
+fn with_xml_namespace<R>(parser: Parser<R>) -> Result<Parser<R>, XMLGeneratorError>
+where
+    R: Resolver,
+    R::Buffer: BufRead,
+{
+    parser
+        .add_schema_from_str(XML_NAMESPACE_SCHEMA)
+        .map_err(|err| ParseError(err.to_string()))
+}
+
+fn generate_schema(filepath: &Path) -> Result<Schemas, XMLGeneratorError> {
+    let path = filepath.canonicalize();
+    if let Err(_err) = path {
+        return Err(FilepathError);
+    }
+
+    let parser = Parser::new()
+        .with_resolver(ChameleonResolver::new(FileResolver::new()))
+        .with_default_namespaces();
+    let schemas = with_xml_namespace(parser)?.add_schema_from_file(path.unwrap());
+
+    if let Err(err) = schemas {
+        return Err(ParseError(err.to_string()));
+    }
+
+    Ok(prepare_schemas(schemas.unwrap().finish()))
+}
+
+fn generate_schema_from_string(string: &str) -> Result<Schemas, XMLGeneratorError> {
+    let parser = Parser::new()
+        .with_resolver(ChameleonResolver::new(FileResolver::new()))
+        .with_default_namespaces();
+    let schemas = with_xml_namespace(parser)?.add_schema_from_str(string);
+
+    if let Err(err) = schemas {
+        return Err(ParseError(err.to_string()));
+    }
+
+    Ok(prepare_schemas(schemas.unwrap().finish()))
+}
+
+fn find_root_tag(contents: &str) -> Option<(usize, usize)> {
+    let mut position = 0;
+    while let Some(offset) = contents[position..].find('<') {
+        let start = position + offset;
+        let rest = &contents[start..];
+        if rest.starts_with("<!--") {
+            position = start + rest.find("-->")? + 3;
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            position = start + rest.find('>')? + 1;
+        } else {
+            return Some((start, start + rest.find('>')?));
+        }
+    }
+
+    None
+}
+
+fn adopt_namespace(contents: String, namespace: &Namespace) -> String {
+    let Some((start, end)) = find_root_tag(&contents) else {
+        return contents;
+    };
+
+    let tag = &contents[start..end];
+    if tag.contains("targetNamespace") {
+        return contents;
+    }
+
+    let name_end = match tag.find(|c: char| c.is_whitespace() || c == '/') {
+        Some(x) => start + x,
+        None => end,
+    };
+
+    let namespace = escape_text(&namespace.to_string()).replace('"', "&quot;");
+    format!(
+        "{} targetNamespace=\"{}\"{}",
+        &contents[..name_end],
+        namespace,
+        &contents[name_end..]
+    )
+}
+
+#[derive(Debug)]
+struct ChameleonResolver<R> {
+    inner: R,
+}
+
+impl<R> ChameleonResolver<R> {
+    fn new(inner: R) -> Self {
+        ChameleonResolver { inner }
+    }
+}
+
+impl<R> Resolver for ChameleonResolver<R>
+where
+    R: Resolver,
+    R::Buffer: Read,
+{
+    type Buffer = Cursor<Vec<u8>>;
+    type Error = String;
+
+    fn resolve(&mut self, req: &ResolveRequest) -> Result<Option<(Url, Self::Buffer)>, String> {
+        let Some((url, mut buffer)) = self.inner.resolve(req).map_err(|err| err.to_string())?
+        else {
+            return Ok(None);
+        };
+
+        let mut contents = String::new();
+        if let Err(err) = buffer.read_to_string(&mut contents) {
+            return Err(format!("{}: {}", url, err));
+        }
+
+        if req.requested_ns.is_none()
+            && let Some(namespace) = &req.current_ns
+        {
+            contents = adopt_namespace(contents, namespace);
+        }
+
+        Ok(Some((url, Cursor::new(contents.into_bytes()))))
+    }
+}
+
+#[derive(Debug)]
+struct StringResolver {
+    base: Url,
+    schemas: HashMap<Url, String>,
+}
+
+impl StringResolver {
+    fn new(schemas: &[(&str, &str)]) -> Result<Self, XMLGeneratorError> {
+        let base = Url::parse("memory:///").unwrap();
+
+        let mut resolver = StringResolver {
+            base,
+            schemas: HashMap::new(),
+        };
+
+        for (name, contents) in schemas.iter() {
+            let url = resolver.get_url(name)?;
+            resolver.schemas.insert(url, contents.to_string());
+        }
+
+        Ok(resolver)
+    }
+
+    fn get_url(&self, name: &str) -> Result<Url, XMLGeneratorError> {
+        let url = self.base.join(name);
+        match url {
+            Ok(x) => Ok(x),
+            Err(err) => Err(InvalidInputError(format!(
+                "Invalid schema name {}: {}",
+                name, err
+            ))),
+        }
+    }
+}
+
+impl Resolver for StringResolver {
+    type Buffer = Cursor<Vec<u8>>;
+    type Error = String;
+
+    fn resolve(&mut self, req: &ResolveRequest) -> Result<Option<(Url, Self::Buffer)>, String> {
+        let base = req.current_location.as_ref().unwrap_or(&self.base);
+        let Ok(url) = base.join(&req.requested_location) else {
+            return Ok(None);
+        };
+
+        let Some(contents) = self.schemas.get(&url) else {
+            return Ok(None);
+        };
+
+        let buffer = Cursor::new(contents.as_bytes().to_vec());
+
+        Ok(Some((url, buffer)))
+    }
+}
+
+fn generate_schema_from_strings(schemas: &[(&str, &str)]) -> Result<Schemas, XMLGeneratorError> {
+    let resolver = StringResolver::new(schemas)?;
+
+    let mut urls = vec![];
+    for (name, _) in schemas.iter() {
+        urls.push(resolver.get_url(name)?);
+    }
+
+    let parser = Parser::new()
+        .with_resolver(ChameleonResolver::new(resolver))
+        .with_default_namespaces();
+    let mut parser = with_xml_namespace(parser)?;
+
+    for url in urls {
+        let result = parser.add_schema_from_url(url);
+        if let Err(err) = result {
+            return Err(ParseError(err.to_string()));
+        }
+
+        parser = result.unwrap();
+    }
+
+    Ok(prepare_schemas(parser.finish()))
+}
+
+fn has_bounded_sequence(
+    meta_types: &MetaTypes,
+    ident: &Ident,
+    bounded: bool,
+    visited: &mut Vec<Ident>,
+) -> bool {
+    if visited.contains(ident) {
+        return false;
+    }
+    visited.push(ident.clone());
+
+    let Some(meta_type) = meta_types.items.get(ident) else {
+        return false;
+    };
+
+    let (group, sequence) = match &meta_type.variant {
+        MetaTypeVariant::All(group) | MetaTypeVariant::Sequence(group) => (group, true),
+        MetaTypeVariant::Choice(group) => (group, false),
+        MetaTypeVariant::Reference(reference) if reference.is_single() => {
+            return has_bounded_sequence(meta_types, &reference.type_, bounded, visited);
+        }
+        _ => return false,
+    };
+
+    if sequence && bounded {
+        return true;
+    }
+
+    for element in group.elements.iter() {
+        if element.element_mode != ElementMode::Group {
+            continue;
+        }
+
+        let ElementMetaVariant::Type(type_) = &element.variant else {
+            continue;
+        };
+
+        if let Some(MetaTypeVariant::Choice(_)) = meta_types.items.get(type_).map(|x| &x.variant) {
+            return true;
+        }
+
+        let bounded =
+            bounded || element.min_occurs == 0 || element.max_occurs > MaxOccurs::Bounded(1);
+        if has_bounded_sequence(meta_types, type_, bounded, visited) {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn get_flattened_types(meta_types: &MetaTypes) -> Vec<Ident> {
+    let mut idents = vec![];
+    for (ident, meta_type) in meta_types.items.iter() {
+        let MetaTypeVariant::ComplexType(complex) = &meta_type.variant else {
+            continue;
+        };
+
+        if !complex.has_complex_content(meta_types) {
+            continue;
+        }
+
+        let content = complex.content.as_ref().unwrap();
+        if !has_bounded_sequence(meta_types, content, false, &mut vec![]) {
+            idents.push(ident.clone());
+        }
+    }
+
+    idents
+}
+
+fn optimise_meta_types(meta_types: MetaTypes, steps: &OptimizerSteps) -> MetaTypes {
+    let mut optimizer = Optimizer::new(meta_types);
+    if steps.remove_empty_enum_variants {
+        optimizer = optimizer.remove_empty_enum_variants();
+    }
+    if steps.remove_empty_enums {
+        optimizer = optimizer.remove_empty_enums();
+    }
+    if steps.remove_duplicate_union_variants {
+        optimizer = optimizer.remove_duplicate_union_variants();
+    }
+    if steps.remove_empty_unions {
+        optimizer = optimizer.remove_empty_unions();
+    }
+    if steps.convert_dynamic_to_choice {
+        optimizer = optimizer.convert_dynamic_to_choice();
+    }
+    let meta_types = optimizer.finish();
+
+    let mut optimizer = if steps.flatten_complex_types {
+        let idents = get_flattened_types(&meta_types);
+        let mut optimizer = Optimizer::new(meta_types);
+        for ident in idents {
+            optimizer = optimizer.flatten_complex_type(ident).unwrap();
+        }
+        optimizer
+    } else {
+        Optimizer::new(meta_types)
+    };
+
+    if steps.flatten_unions {
+        optimizer = optimizer.flatten_unions();
+    }
+    if steps.merge_enum_unions {
+        optimizer = optimizer.merge_enum_unions();
+    }
+    if steps.resolve_typedefs {
+        optimizer = optimizer.resolve_typedefs();
+    }
+    if steps.remove_duplicates {
+        optimizer = optimizer.remove_duplicates();
+    }
+    if steps.merge_choice_cardinalities {
+        optimizer = optimizer.merge_choice_cardinalities();
+    }
+    optimizer.finish()
+}
+
+fn generate_meta_types(
+    schemas: &Schemas,
+    steps: &OptimizerSteps,
+) -> Result<MetaTypes, XMLGeneratorError> {
+    let meta_types = Interpreter::new(schemas).with_buildin_types();
+    if let Err(err) = meta_types {
+        return Err(ParseError(err.to_string()));
+    }
+
+    let meta_types = meta_types.unwrap().with_default_typedefs();
+    if let Err(err) = meta_types {
+        return Err(ParseError(err.to_string()));
+    }
+
+    let xs = schemas.resolve_namespace(&Some(Namespace::XS));
+    let date_time_stamp = Ident::type_("dateTimeStamp").with_ns(xs);
+    let meta_types = meta_types
+        .unwrap()
+        .with_typedef(date_time_stamp, Ident::STRING);
+    if let Err(err) = meta_types {
+        return Err(ParseError(err.to_string()));
+    }
+
+    let meta_types = meta_types.unwrap().with_xs_any_type();
+    if let Err(err) = meta_types {
+        return Err(ParseError(err.to_string()));
+    }
+
+    let meta_types = meta_types.unwrap().finish();
+
+    if let Err(err) = meta_types {
+        return Err(ParseError(err.to_string()));
+    }
+
+    Ok(optimise_meta_types(meta_types.unwrap(), steps))
+}
+
+fn get_namespace_uri(data_types: &DataTypes, ident: &Ident) -> String {
+    let modules = &data_types.meta.types.modules;
+    let module = ident.ns.and_then(|id| modules.get(&id));
+    match module.and_then(|x| x.namespace.as_ref()) {
+        Some(namespace) => namespace.to_string(),
+        None => "no namespace".to_string(),
+    }
+}
+
+fn check_struct_names(data_types: &DataTypes) -> Result<(), XMLGeneratorError> {
+    let mut names: HashMap<String, &Ident> = HashMap::new();
+    let mut conflicts = vec![];
+    for ident in data_types.items.keys() {
+        let Some(name) = get_struct_name(data_types, ident) else {
+            continue;
+        };
+
+        let Some(other) = names.get(&name) else {
+            names.insert(name, ident);
+            continue;
+        };
+
+        let mut namespaces = [
+            get_namespace_uri(data_types, other),
+            get_namespace_uri(data_types, ident),
+        ];
+        namespaces.sort();
+        conflicts.push(format!(
+            "Type `{}` is defined in both `{}` and `{}`",
+            ident.name, namespaces[0], namespaces[1]
+        ));
+    }
+
+    conflicts.sort();
+    match conflicts.into_iter().next() {
+        Some(conflict) => Err(InvalidInputError(conflict)),
+        None => Ok(()),
+    }
+}
+
+fn generate_data_types(meta_types: &MetaTypes) -> Result<DataTypes<'_>, XMLGeneratorError> {
+    let data_types = Generator::new(meta_types)
+        .flags(GeneratorFlags::all())
+        .generate_named_types();
+
+    if let Err(err) = data_types {
+        return Err(ParseError(err.to_string()));
+    }
+
+    let data_types = data_types.unwrap().finish();
+    check_struct_names(&data_types)?;
+    Ok(data_types)
+}
+
+fn generate_xml_from_schemas(
+    schemas: &Schemas,
+    config: &GeneratorConfig,
+) -> Result<(GeneratedDocument, GenerationReport), XMLGeneratorError> {
+    validate_declarations(schemas)?;
+    let meta_types = generate_meta_types(schemas, &config.optimizer_steps)?;
+    let data_types = generate_data_types(&meta_types)?;
+    generate_xml_data(schemas, &data_types, config)
+}
+
+pub fn generate_xml(filepath: Box<Path>) -> Result<String, XMLGeneratorError> {
+    generate_xml_with_config(filepath, &GeneratorConfig::default())
+}
+
+pub fn generate_xml_with_config(
+    filepath: Box<Path>,
+    config: &GeneratorConfig,
+) -> Result<String, XMLGeneratorError> {
+    let schemas = generate_schema(&filepath)?;
+    let (document, _) = generate_xml_from_schemas(&schemas, config)?;
+    Ok(document.xml)
+}
+
+pub fn generate_xml_from_string(xsd_string: &str) -> Result<String, XMLGeneratorError> {
+    generate_xml_from_string_with_config(xsd_string, &GeneratorConfig::default())
+}
+
+pub fn generate_xml_from_string_with_config(
+    xsd_string: &str,
+    config: &GeneratorConfig,
+) -> Result<String, XMLGeneratorError> {
+    let schemas = generate_schema_from_string(xsd_string)?;
+    let (document, _) = generate_xml_from_schemas(&schemas, config)?;
+    Ok(document.xml)
+}
+
+pub fn generate_xml_bytes(
+    xsd_string: &str,
+    config: &GeneratorConfig,
+) -> Result<Vec<u8>, XMLGeneratorError> {
+    let xml = generate_xml_from_string_with_config(xsd_string, config)?;
+    Ok(xml.into_bytes())
+}
+
+#[cfg(feature = "gzip")]
+pub fn generate_xml_from_gz(bytes: &[u8]) -> Result<String, XMLGeneratorError> {
+    generate_xml_from_gz_with_config(bytes, &GeneratorConfig::default())
+}
+
+#[cfg(feature = "gzip")]
+pub fn generate_xml_from_gz_with_config(
+    bytes: &[u8],
+    config: &GeneratorConfig,
+) -> Result<String, XMLGeneratorError> {
+    use flate2::read::GzDecoder;
+
+    let mut contents = vec![];
+    if let Err(err) = GzDecoder::new(bytes).read_to_end(&mut contents) {
+        return Err(IOError(format!("Failed to decompress schema: {}", err)));
+    }
+
+    let xsd_string = match String::from_utf8(contents) {
+        Ok(x) => x,
+        Err(err) => {
+            return Err(XMLGeneratorError::StringConversionError(err.to_string()));
+        }
+    };
+
+    generate_xml_from_string_with_config(&xsd_string, config)
+}
+
+pub fn generate_with_report(
+    xsd_string: &str,
+    config: &GeneratorConfig,
+) -> Result<(String, GenerationReport), XMLGeneratorError> {
+    let schemas = generate_schema_from_string(xsd_string)?;
+    let (document, report) = generate_xml_from_schemas(&schemas, config)?;
+    Ok((document.xml, report))
+}
+
+pub fn generate_with_coverage(
+    xsd_string: &str,
+    config: &GeneratorConfig,
+) -> Result<(String, CoverageSummary), XMLGeneratorError> {
+    let (xml, report) = generate_with_report(xsd_string, config)?;
+    Ok((xml, report.coverage))
+}
+
+pub fn generate_detailed(
+    xsd_string: &str,
+    config: &GeneratorConfig,
+) -> Result<GeneratedDocument, XMLGeneratorError> {
+    let schemas = generate_schema_from_string(xsd_string)?;
+    let (document, _) = generate_xml_from_schemas(&schemas, config)?;
+    Ok(document)
+}
+
+pub fn generate_xml_from_strings(schemas: &[(&str, &str)]) -> Result<String, XMLGeneratorError> {
+    generate_xml_from_strings_with_config(schemas, &GeneratorConfig::default())
+}
+
+pub fn generate_xml_from_strings_with_config(
+    schemas: &[(&str, &str)],
+    config: &GeneratorConfig,
+) -> Result<String, XMLGeneratorError> {
+    let schemas = generate_schema_from_strings(schemas)?;
+    let (document, _) = generate_xml_from_schemas(&schemas, config)?;
+    Ok(document.xml)
+}
+
+pub fn generate_xml_to_file(xsd_string: &str, filepath: &Path) -> Result<(), XMLGeneratorError> {
+    generate_xml_to_file_with_config(xsd_string, filepath, &GeneratorConfig::default())
+}
+
+pub fn generate_xml_to_file_with_config(
+    xsd_string: &str,
+    filepath: &Path,
+    config: &GeneratorConfig,
+) -> Result<(), XMLGeneratorError> {
+    let xml = generate_xml_from_string_with_config(xsd_string, config)?;
+
+    if let Err(err) = fs::write(filepath, xml) {
+        return Err(IOError(format!("{}: {}", filepath.display(), err)));
+    }
+
+    Ok(())
+}
+
+pub fn generate_all_roots(xsd_string: &str) -> Result<Vec<(String, String)>, XMLGeneratorError> {
+    generate_all_roots_with_config(xsd_string, &GeneratorConfig::default())
+}
+
+pub fn generate_all_roots_with_config(
+    xsd_string: &str,
+    config: &GeneratorConfig,
+) -> Result<Vec<(String, String)>, XMLGeneratorError> {
+    let schemas = generate_schema_from_string(xsd_string)?;
+    validate_declarations(&schemas)?;
+    let meta_types = generate_meta_types(&schemas, &config.optimizer_steps)?;
+    let data_types = generate_data_types(&meta_types)?;
+    generate_all_roots_data(&schemas, &data_types, config)
+}
+
+pub fn generate_all_roots_to_dir(xsd_string: &str, dir: &Path) -> Result<(), XMLGeneratorError> {
+    generate_all_roots_to_dir_with_config(xsd_string, dir, &GeneratorConfig::default())
+}
+
+pub fn generate_all_roots_to_dir_with_config(
+    xsd_string: &str,
+    dir: &Path,
+    config: &GeneratorConfig,
+) -> Result<(), XMLGeneratorError> {
+    let documents = generate_all_roots_with_config(xsd_string, config)?;
+
+    if let Err(err) = fs::create_dir_all(dir) {
+        return Err(IOError(format!("{}: {}", dir.display(), err)));
+    }
+
+    for (name, xml) in documents {
+        let filepath = dir.join(format!("{}.xml", name));
+        if let Err(err) = fs::write(&filepath, xml) {
+            return Err(IOError(format!("{}: {}", filepath.display(), err)));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn estimate_max_size(
+    xsd_string: &str,
+    config: &GeneratorConfig,
+) -> Result<usize, XMLGeneratorError> {
+    let schema = generate_schema_from_string(xsd_string)?;
+    validate_declarations(&schema)?;
+    let meta_types = generate_meta_types(&schema, &config.optimizer_steps)?;
     let data_types = generate_data_types(&meta_types)?;
 
-    generate_xml_data(&data_types)
+    let structs = get_structs(&data_types)?;
+    let root = find_root(&structs)?;
+
+    Ok(estimate_element_count(root, &structs, config, 0))
+}
+
+pub fn list_root_candidates(xsd_string: &str) -> Result<Vec<String>, XMLGeneratorError> {
+    let schema = generate_schema_from_string(xsd_string)?;
+    validate_declarations(&schema)?;
+    let meta_types = generate_meta_types(&schema, &OptimizerSteps::default())?;
+    let data_types = generate_data_types(&meta_types)?;
+
+    let structs = get_structs(&data_types)?;
+    let roots = find_roots(&structs);
+
+    Ok(roots.into_iter().map(|x| get_root_name(x).to_string()).collect())
+}
+
+fn has_generator(field_type: &FieldType) -> bool {
+    let name = field_type.name.as_str();
+    if get_natural_bounds(name).is_some()
+        || matches!(name, "f32" | "f64" | "bool" | "char" | "String")
+    {
+        return true;
+    }
+
+    let facets = field_type.facets.as_ref();
+    facets.is_some_and(|x| x.fixed.is_some() || !x.enumerations.is_empty() || x.pattern.is_some())
+}
+
+fn check_generators(
+    structure: &StructInfo,
+    structs: &[StructInfo],
+    visited: &mut Vec<String>,
+) -> Result<(), XMLGeneratorError> {
+    if visited.contains(&structure.name) {
+        return Ok(());
+    }
+    visited.push(structure.name.clone());
+
+    for field in structure.fields.iter() {
+        if field.field_type.min_occurrences == Some(0) {
+            continue;
+        }
+
+        let branches = match &field.substitutes {
+            Some(substitutes) => substitutes.iter().map(|(name, _, x)| (name, x)).collect(),
+            None => vec![(&field.xml_name, &field.field_type)],
+        };
+
+        for (name, field_type) in branches {
+            match get_field_struct(structs, &field_type.name) {
+                Some(child) => check_generators(child, structs, visited)?,
+                None if has_generator(field_type) => {}
+                None => {
+                    let kind = match field.kind {
+                        FieldKind::Attribute => "attribute",
+                        _ => "element",
+                    };
+                    return Err(InvalidInputError(format!(
+                        "No generator for type `{}` of required {} `{}`",
+                        field_type.name, kind, name
+                    )));
+                }
+            }
+        }
+    }
+
+    Ok(())
 }
 
-pub fn generate_xml_from_string(xsd_string: &String) -> Result<String, XMLGeneratorError> {
+pub fn validate_schema(xsd_string: &str) -> Result<(), XMLGeneratorError> {
     let schema = generate_schema_from_string(xsd_string)?;
-    let meta_types = generate_meta_types(&schema, true)?;
+    validate_declarations(&schema)?;
+    let meta_types = generate_meta_types(&schema, &OptimizerSteps::default())?;
     let data_types = generate_data_types(&meta_types)?;
-    generate_xml_data(&data_types)
+
+    let mut structs = get_structs(&data_types)?;
+    apply_restrictions(&mut structs, &schema, &data_types);
+
+    let root = find_root(&structs)?;
+    check_generators(root, &structs, &mut vec![])
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct XMLGenerator {
+    config: GeneratorConfig,
+}
+
+impl XMLGenerator {
+    pub fn new(config: GeneratorConfig) -> Self {
+        XMLGenerator { config }
+    }
+
+    pub fn builder() -> GeneratorBuilder {
+        GeneratorBuilder::default()
+    }
+
+    pub fn config(&self) -> &GeneratorConfig {
+        &self.config
+    }
+
+    pub fn generate(&self, filepath: Box<Path>) -> Result<String, XMLGeneratorError> {
+        generate_xml_with_config(filepath, &self.config)
+    }
+
+    pub fn generate_from_string(&self, xsd_string: &str) -> Result<String, XMLGeneratorError> {
+        generate_xml_from_string_with_config(xsd_string, &self.config)
+    }
+
+    pub fn generate_with_report(
+        &self,
+        xsd_string: &str,
+    ) -> Result<(String, GenerationReport), XMLGeneratorError> {
+        generate_with_report(xsd_string, &self.config)
+    }
+
+    pub fn generate_with_coverage(
+        &self,
+        xsd_string: &str,
+    ) -> Result<(String, CoverageSummary), XMLGeneratorError> {
+        generate_with_coverage(xsd_string, &self.config)
+    }
+
+    pub fn generate_from_strings(
+        &self,
+        schemas: &[(&str, &str)],
+    ) -> Result<String, XMLGeneratorError> {
+        generate_xml_from_strings_with_config(schemas, &self.config)
+    }
+
+    pub fn estimate_max_size(&self, xsd_string: &str) -> Result<usize, XMLGeneratorError> {
+        estimate_max_size(xsd_string, &self.config)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GeneratorBuilder {
+    config: GeneratorConfig,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GeneratorConfigBuilder {
+    config: GeneratorConfig,
+}
+
+macro_rules! impl_config_setters {
+    ($builder:ty) => {
+        impl $builder {
+            pub fn exclude(mut self, name: &str) -> Self {
+                self.config.exclude.push(name.to_string());
+                self
+            }
+
+            pub fn attribute_mode(mut self, attribute_mode: AttributeMode) -> Self {
+                self.config.attribute_mode = attribute_mode;
+                self
+            }
+
+            pub fn max_depth(mut self, max_depth: usize) -> Self {
+                self.config.max_depth = max_depth;
+                self
+            }
+
+            pub fn annotate(mut self, annotate: bool) -> Self {
+                self.config.annotate = annotate;
+                self
+            }
+
+            pub fn max_repeats(mut self, max_repeats: u64) -> Self {
+                self.config.max_repeats = max_repeats;
+                self
+            }
+
+            pub fn seed(mut self, seed: u64) -> Self {
+                self.config.seed = Some(seed);
+                self
+            }
+
+            pub fn float_special_values(mut self, float_special_values: bool) -> Self {
+                self.config.float_special_values = float_special_values;
+                self
+            }
+
+            pub fn xsi_type(mut self, xsi_type: bool) -> Self {
+                self.config.xsi_type = xsi_type;
+                self
+            }
+
+            pub fn locale(mut self, locale: Locale) -> Self {
+                self.config.locale = Some(locale);
+                self
+            }
+
+            pub fn xml_lang(mut self, xml_lang: bool) -> Self {
+                self.config.xml_lang = xml_lang;
+                self
+            }
+
+            pub fn name_heuristics(mut self, name_heuristics: bool) -> Self {
+                self.config.name_heuristics = name_heuristics;
+                self
+            }
+
+            pub fn cdata_heuristics(mut self, cdata_heuristics: bool) -> Self {
+                self.config.cdata_heuristics = cdata_heuristics;
+                self
+            }
+
+            pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+                self.config.max_bytes = Some(max_bytes);
+                self
+            }
+
+            pub fn max_total_elements(mut self, max_total_elements: usize) -> Self {
+                self.config.max_total_elements = Some(max_total_elements);
+                self
+            }
+
+            pub fn max_text_length(mut self, max_text_length: usize) -> Self {
+                self.config.max_text_length = Some(max_text_length);
+                self
+            }
+
+            pub fn skip_wildcards(mut self, skip_wildcards: bool) -> Self {
+                self.config.skip_wildcards = skip_wildcards;
+                self
+            }
+
+            pub fn open_content(mut self, open_content: bool) -> Self {
+                self.config.open_content = open_content;
+                self
+            }
+
+            pub fn optional_probability(mut self, optional_probability: f64) -> Self {
+                self.config.optional_probability = Some(optional_probability);
+                self
+            }
+
+            pub fn mode(mut self, mode: GenerationMode) -> Self {
+                self.config.mode = mode;
+                self
+            }
+
+            pub fn pretty(mut self, pretty: bool) -> Self {
+                self.config.pretty = pretty;
+                self
+            }
+
+            pub fn sort_all_groups(mut self, sort_all_groups: bool) -> Self {
+                self.config.sort_all_groups = sort_all_groups;
+                self
+            }
+
+            pub fn indent(mut self, indent: usize) -> Self {
+                self.config.indent = Some(indent);
+                self
+            }
+
+            pub fn strict(mut self, strict: bool) -> Self {
+                self.config.strict = strict;
+                self
+            }
+
+            pub fn timezone(mut self, timezone: TimezoneMode) -> Self {
+                self.config.timezone = timezone;
+                self
+            }
+
+            pub fn enumeration_strategy(mut self, enumeration_strategy: EnumerationStrategy) -> Self {
+                self.config.enumeration_strategy = enumeration_strategy;
+                self
+            }
+
+            pub fn canonical_decimals(mut self, canonical_decimals: bool) -> Self {
+                self.config.canonical_decimals = canonical_decimals;
+                self
+            }
+
+            pub fn lowercase_hex(mut self, lowercase_hex: bool) -> Self {
+                self.config.lowercase_hex = lowercase_hex;
+                self
+            }
+
+            pub fn fraction_digits(mut self, fraction_digits: u32) -> Self {
+                self.config.fraction_digits = Some(fraction_digits);
+                self
+            }
+
+            pub fn value_provider(mut self, provider: fn(&ValueContext) -> Option<String>) -> Self {
+                self.config.value_providers.push(provider);
+                self
+            }
+
+            pub fn name_transform(mut self, name_transform: fn(&str) -> String) -> Self {
+                self.config.name_transform = Some(name_transform);
+                self
+            }
+
+            pub fn value_pool(mut self, name: &str, values: &[&str]) -> Self {
+                let values = values.iter().map(|x| x.to_string()).collect();
+                self.config.value_pools.insert(name.to_string(), values);
+                self
+            }
+
+            pub fn namespace_prefix(mut self, namespace: &str, prefix: &str) -> Self {
+                let (namespace, prefix) = (namespace.to_string(), prefix.to_string());
+                self.config.namespace_prefixes.insert(namespace, prefix);
+                self
+            }
+
+            pub fn choice_weight(mut self, name: &str, branch: &str, weight: f64) -> Self {
+                let key = (name.to_string(), branch.to_string());
+                self.config.choice_weights.insert(key, weight);
+                self
+            }
+
+            pub fn optimizer_steps(mut self, optimizer_steps: OptimizerSteps) -> Self {
+                self.config.optimizer_steps = optimizer_steps;
+                self
+            }
+        }
+    };
+}
+
+impl_config_setters!(GeneratorBuilder);
+impl_config_setters!(GeneratorConfigBuilder);
+
+impl GeneratorBuilder {
+    pub fn build(self) -> XMLGenerator {
+        XMLGenerator::new(self.config)
+    }
+
+    pub fn build_from_str(self, xsd_string: &str) -> Result<String, XMLGeneratorError> {
+        self.build().generate_from_string(xsd_string)
+    }
+}
+
+impl GeneratorConfigBuilder {
+    pub fn build(self) -> Result<GeneratorConfig, XMLGeneratorError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+#[cfg(feature = "test-utils")]
+fn check_well_formed(xml: &str) -> Result<(), String> {
+    use quick_xml::Reader;
+    use quick_xml::events::Event;
+
+    let mut reader = Reader::from_str(xml);
+    let mut open: Vec<String> = vec![];
+    let mut roots = 0;
+    loop {
+        let event = reader.read_event();
+        match event {
+            Ok(Event::Start(x)) => {
+                if open.is_empty() {
+                    roots += 1;
+                }
+
+                open.push(String::from_utf8_lossy(x.name().as_ref()).to_string());
+            }
+            Ok(Event::Empty(_)) if open.is_empty() => roots += 1,
+            Ok(Event::End(_)) => {
+                open.pop();
+            }
+            Ok(Event::Text(x)) => {
+                let text = x.unescape();
+                let Ok(text) = text else {
+                    return Err(format!(
+                        "Malformed text in `{}`: {:?}",
+                        open.join("/"),
+                        text.err()
+                    ));
+                };
+
+                if open.is_empty() && !text.trim().is_empty() {
+                    return Err(format!("Text outside the root: {}", text));
+                }
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(err) => {
+                return Err(format!(
+                    "Malformed XML at position {}: {}",
+                    reader.buffer_position(),
+                    err
+                ));
+            }
+        }
+    }
+
+    if !open.is_empty() {
+        return Err(format!("Unclosed elements: {:?}", open));
+    }
+
+    if roots != 1 {
+        return Err("Expected exactly one root element".to_string());
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "test-utils")]
+pub fn is_well_formed(xml: &str) -> bool {
+    check_well_formed(xml).is_ok()
+}
+
+#[cfg(feature = "test-utils")]
+pub fn assert_well_formed(xml: &str) {
+    if let Err(err) = check_well_formed(xml) {
+        panic!("{}", err);
+    }
 }
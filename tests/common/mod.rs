@@ -0,0 +1,12 @@
+pub fn get_values(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+
+    let mut values = vec![];
+    for part in xml.split(&open).skip(1) {
+        let end = part.find(&close).unwrap();
+        values.push(part[..end].to_string());
+    }
+
+    values
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use std::path;
+    use xmlgenerator::{GeneratorConfig, Locale, XMLGenerator, XMLGeneratorError};
+
+    #[test]
+    fn test_builder_options() {
+        let generator = XMLGenerator::builder()
+            .seed(42)
+            .max_repeats(1)
+            .exclude("note")
+            .locale(Locale::DeDe)
+            .build();
+
+        let config = generator.config();
+        assert_eq!(config.seed, Some(42));
+        assert_eq!(config.max_repeats, 1);
+        assert_eq!(config.exclude, vec!["note".to_string()]);
+        assert_eq!(config.locale, Some(Locale::DeDe));
+
+        let filepath = path::absolute("./examples/example.xsd").unwrap();
+        let first = generator
+            .generate(filepath.clone().into_boxed_path())
+            .unwrap();
+        let second = generator.generate(filepath.into_boxed_path()).unwrap();
+        assert_eq!(first, second);
+        assert!(!first.contains("<note>"));
+        assert!(first.contains("<title>"));
+    }
+
+    #[test]
+    fn test_builder_from_str() {
+        let filepath = path::absolute("./examples/example.xsd").unwrap();
+        let xsd = std::fs::read_to_string(filepath).unwrap();
+
+        let first = XMLGenerator::builder()
+            .seed(7)
+            .build_from_str(&xsd)
+            .unwrap();
+        let second = XMLGenerator::builder()
+            .seed(7)
+            .build_from_str(&xsd)
+            .unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_config_builder() {
+        let config = GeneratorConfig::builder()
+            .seed(42)
+            .indent(2)
+            .locale(Locale::FrFr)
+            .max_repeats(5)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.seed, Some(42));
+        assert_eq!(config.indent, Some(2));
+        assert_eq!(config.locale, Some(Locale::FrFr));
+        assert_eq!(config.max_repeats, 5);
+        assert_eq!(config.max_depth, GeneratorConfig::default().max_depth);
+
+        let error = GeneratorConfig::builder().optional_probability(1.5).build();
+        match error {
+            Err(XMLGeneratorError::InvalidInputError(error)) => {
+                assert!(error.contains("optional_probability"), "{}", error)
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+}
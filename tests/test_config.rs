@@ -0,0 +1,1131 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::get_values;
+    use fake::locales::{Data, FR_FR};
+    use std::collections::HashSet;
+    use std::path;
+    use xmlgenerator::{
+        AttributeMode, GeneratorConfig, Locale, OptimizerSteps, ValueContext, XMLGeneratorError,
+        assert_well_formed, estimate_max_size, generate_with_report,
+        generate_xml_from_string_with_config, generate_xml_with_config,
+    };
+
+    fn generate_example(filename: &str, config: &GeneratorConfig) -> String {
+        let filepath = path::absolute("./examples").unwrap().join(filename);
+        let xml = generate_xml_with_config(filepath.into_boxed_path(), config);
+
+        match xml {
+            Ok(result) => result,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_exclude_optional_element() {
+        let config = GeneratorConfig {
+            exclude: vec!["note".to_string()],
+            ..Default::default()
+        };
+
+        let xml = generate_example("example.xsd", &config);
+        assert!(!xml.contains("<note>"));
+        assert!(xml.contains("<title>"));
+    }
+
+    #[test]
+    fn test_attribute_mode() {
+        let mut editions = HashSet::new();
+        for seed in 0..20 {
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                ..Default::default()
+            };
+
+            let xml = generate_example("attributes.xsd", &config);
+            assert!(xml.contains("isbn=\""));
+            editions.insert(xml.contains("edition=\""));
+        }
+        assert_eq!(editions.len(), 2);
+
+        let config = GeneratorConfig {
+            attribute_mode: AttributeMode::RequiredOnly,
+            ..Default::default()
+        };
+
+        let xml = generate_example("attributes.xsd", &config);
+        assert!(xml.contains("isbn=\""));
+        assert!(!xml.contains("edition=\""));
+
+        let mut config = GeneratorConfig {
+            attribute_mode: AttributeMode::None,
+            ..Default::default()
+        };
+
+        let xsd = std::fs::read_to_string("./examples/attributes.xsd").unwrap();
+        let (xml, report) = generate_with_report(&xsd, &config).unwrap();
+        assert!(xml.contains("<book>"), "{}", xml);
+        assert!(!xml.contains("isbn=\""));
+        assert!(!xml.contains("edition=\""));
+        assert!(report.used_fallbacks);
+        assert!(report.workarounds[0].contains("`isbn`"));
+
+        config.strict = true;
+        match generate_with_report(&xsd, &config) {
+            Err(XMLGeneratorError::XMLGenerationError(err)) => assert!(err.contains("`isbn`")),
+            other => panic!("Expected a strict mode error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_optional_probability() {
+        for (probability, included) in [(0.0, false), (1.0, true)] {
+            let config = GeneratorConfig {
+                optional_probability: Some(probability),
+                ..Default::default()
+            };
+
+            for _ in 0..10 {
+                let xml = generate_example("attributes.xsd", &config);
+                assert!(xml.contains("isbn=\""));
+                assert_eq!(xml.contains("edition=\""), included);
+
+                let xml = generate_example("example.xsd", &config);
+                assert!(xml.contains("<title>"));
+                assert_eq!(xml.contains("<note>"), included);
+            }
+        }
+
+        let config = GeneratorConfig {
+            optional_probability: Some(1.5),
+            ..Default::default()
+        };
+
+        let filepath = path::absolute("./examples/example.xsd").unwrap();
+        let xml = generate_xml_with_config(filepath.into_boxed_path(), &config);
+        assert!(xml.is_err());
+    }
+
+    #[test]
+    fn test_optional_elements_vary_by_default() {
+        let mut included = vec![];
+        for seed in 0..20 {
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                ..Default::default()
+            };
+
+            let xml = generate_example("example.xsd", &config);
+            assert!(xml.contains("<title>"));
+            included.push(xml.contains("<note>"));
+        }
+
+        assert!(included.contains(&true));
+        assert!(included.contains(&false));
+    }
+
+    #[test]
+    fn test_validate_config() {
+        assert!(GeneratorConfig::default().validate().is_ok());
+
+        let config = GeneratorConfig {
+            optional_probability: Some(1.5),
+            ..Default::default()
+        };
+        match config.validate() {
+            Err(XMLGeneratorError::InvalidInputError(error)) => assert_eq!(
+                error,
+                "Invalid generator config: optional_probability must be between 0 and 1, got 1.5"
+            ),
+            other => panic!("{:?}", other),
+        }
+
+        let mut config = GeneratorConfig {
+            optional_probability: Some(-0.5),
+            max_bytes: Some(10),
+            ..Default::default()
+        };
+        config.value_pools.insert("name".to_string(), vec![]);
+        let branch = ("feed".to_string(), "post".to_string());
+        config.choice_weights.insert(branch, -1.0);
+        let Err(XMLGeneratorError::InvalidInputError(error)) = config.validate() else {
+            panic!("Expected an invalid input error");
+        };
+        assert!(error.contains("got -0.5"));
+        assert!(error.contains("max_bytes must be at least 39 to fit the XML declaration, got 10"));
+        assert!(error.contains("Value pool for `name` has no values"));
+        assert!(error.contains("Weight of `post` in `feed` must be a non-negative number, got -1"));
+
+        let filepath = path::absolute("./examples/example.xsd").unwrap();
+        let xml = generate_xml_with_config(filepath.into_boxed_path(), &config);
+        assert!(matches!(xml, Err(XMLGeneratorError::InvalidInputError(_))));
+    }
+
+    #[test]
+    fn test_pretty_and_indent() {
+        let config = GeneratorConfig {
+            seed: Some(7),
+            ..Default::default()
+        };
+        let xml = generate_example("example.xsd", &config);
+        assert!(xml.contains("\n\t<"));
+
+        let indented = generate_example(
+            "example.xsd",
+            &GeneratorConfig {
+                indent: Some(2),
+                ..config.clone()
+            },
+        );
+        assert!(!indented.contains('\t'));
+        assert!(indented.contains("\n  <"));
+        assert_eq!(indented, xml.replace('\t', "  "));
+
+        let compact = generate_example(
+            "example.xsd",
+            &GeneratorConfig {
+                pretty: false,
+                ..config
+            },
+        );
+        assert!(!compact.contains('\n'));
+        assert_eq!(compact, xml.replace(['\n', '\t'], ""));
+    }
+
+    #[test]
+    fn test_layout_keeps_preserved_text() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="preservedType">
+    <xs:restriction base="xs:string">
+      <xs:whiteSpace value="preserve"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="listing">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="text" type="preservedType"/>
+        <xs:element name="code" type="preservedType"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let text = "  first line\n\tsecond line\n    third  ";
+        let code = "if a < b && c {\n\t<tag>\n<other/>\n}";
+        let config = GeneratorConfig::builder()
+            .cdata_heuristics(true)
+            .value_pool("text", &[text])
+            .value_pool("code", &[code])
+            .build()
+            .unwrap();
+
+        let layouts = [(true, None), (true, Some(4)), (false, None)];
+        for (pretty, indent) in layouts {
+            let config = GeneratorConfig {
+                pretty,
+                indent,
+                ..config.clone()
+            };
+
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            assert_well_formed(&xml);
+            assert!(xml.contains(&format!("<text>{}</text>", text)), "{}", xml);
+            assert!(
+                xml.contains(&format!("<code><![CDATA[{}]]></code>", code)),
+                "{}",
+                xml
+            );
+        }
+    }
+
+    #[test]
+    fn test_value_pools_apply_to_every_occurrence() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="code" type="xs:string"/>
+  <xs:complexType name="itemType">
+    <xs:sequence>
+      <xs:element ref="code"/>
+      <xs:element name="status" type="xs:string" fixed="active"/>
+    </xs:sequence>
+    <xs:attribute name="code" type="xs:string" use="required"/>
+  </xs:complexType>
+  <xs:element name="order">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element ref="code"/>
+        <xs:element name="item" type="itemType" maxOccurs="unbounded"/>
+        <xs:element name="backup">
+          <xs:complexType>
+            <xs:sequence>
+              <xs:element name="code" type="xs:string" maxOccurs="unbounded"/>
+            </xs:sequence>
+          </xs:complexType>
+        </xs:element>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let pool = ["A1", "B2"];
+        let mut config = GeneratorConfig {
+            max_repeats: 5,
+            ..Default::default()
+        };
+        for name in ["code", "status"] {
+            let values = pool.iter().map(|x| x.to_string()).collect();
+            config.value_pools.insert(name.to_string(), values);
+        }
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+
+            let elements: Vec<&str> = xml.split("<code>").skip(1).collect();
+            assert!(elements.len() >= 3);
+            for part in elements {
+                let value = &part[..part.find("</code>").unwrap()];
+                assert!(pool.contains(&value), "{}", xml);
+            }
+
+            let attributes: Vec<&str> = xml.split(" code=\"").skip(1).collect();
+            assert!(!attributes.is_empty());
+            for part in attributes {
+                let value = &part[..part.find('"').unwrap()];
+                assert!(pool.contains(&value), "{}", xml);
+            }
+
+            let statuses = xml.matches("<status>").count();
+            assert_eq!(xml.matches("<status>active</status>").count(), statuses);
+        }
+    }
+
+    fn order_id(context: &ValueContext) -> Option<String> {
+        match context.path.as_slice() {
+            [.., parent, id] if parent == "order" && id == "id" => Some("ORDER-1".to_string()),
+            _ => None,
+        }
+    }
+
+    fn schema_type(context: &ValueContext) -> Option<String> {
+        match context.element_name.as_str() {
+            "id" => None,
+            _ => Some(format!("type:{}", context.type_name)),
+        }
+    }
+
+    #[test]
+    fn test_value_provider_context() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="nameType">
+    <xs:restriction base="xs:string"/>
+  </xs:simpleType>
+  <xs:element name="order">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="id" type="xs:string"/>
+        <xs:element name="customer">
+          <xs:complexType>
+            <xs:sequence>
+              <xs:element name="id" type="xs:string"/>
+              <xs:element name="name" type="nameType"/>
+              <xs:element name="email" type="xs:string"/>
+              <xs:element name="note">
+                <xs:simpleType>
+                  <xs:restriction base="xs:string"/>
+                </xs:simpleType>
+              </xs:element>
+            </xs:sequence>
+          </xs:complexType>
+        </xs:element>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            value_providers: vec![order_id, schema_type],
+            ..Default::default()
+        };
+
+        for _ in 0..5 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            let ids = get_values(&xml, "id");
+            assert_eq!(ids.len(), 2, "{}", xml);
+            assert_eq!(ids[0], "ORDER-1");
+            assert_ne!(ids[1], "ORDER-1");
+            assert_eq!(get_values(&xml, "name"), vec!["type:nameType"]);
+            assert_eq!(get_values(&xml, "email"), vec!["type:xs:string"]);
+            assert_eq!(get_values(&xml, "note"), vec!["type:"]);
+        }
+    }
+
+    #[test]
+    fn test_cdata_heuristics() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="page">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="markup" type="xs:string"/>
+        <xs:element name="plain" type="xs:string"/>
+        <xs:element name="nested" type="xs:string"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let mut config = GeneratorConfig::default();
+        for (name, value) in [
+            ("markup", "<b>Fish</b> & <i>Chips</i>"),
+            ("plain", "Fish & Chips"),
+            ("nested", "<a>]]></a>"),
+        ] {
+            config
+                .value_pools
+                .insert(name.to_string(), vec![value.to_string()]);
+        }
+
+        let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+        assert!(
+            xml.contains("<markup>&lt;b&gt;Fish&lt;/b&gt; &amp; &lt;i&gt;Chips&lt;/i&gt;</markup>")
+        );
+        assert!(!xml.contains("CDATA"));
+
+        config.cdata_heuristics = true;
+        let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+        assert_well_formed(&xml);
+        assert!(xml.contains("<markup><![CDATA[<b>Fish</b> & <i>Chips</i>]]></markup>"));
+        assert!(xml.contains("<plain>Fish &amp; Chips</plain>"));
+        assert!(xml.contains("<nested><![CDATA[<a>]]]]><![CDATA[></a>]]></nested>"));
+    }
+
+    #[test]
+    fn test_attribute_without_use_is_optional() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="item">
+    <xs:complexType>
+      <xs:attribute name="code" type="xs:string" use="required"/>
+      <xs:attribute name="label" type="xs:string"/>
+      <xs:attribute name="count" type="xs:int" default="4"/>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let mut labels = HashSet::new();
+        for seed in 0..20 {
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                ..Default::default()
+            };
+
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            assert!(xml.contains("code=\""));
+            labels.insert(xml.contains("label=\""));
+        }
+        assert_eq!(labels.len(), 2);
+
+        let config = GeneratorConfig {
+            attribute_mode: AttributeMode::RequiredOnly,
+            ..Default::default()
+        };
+
+        let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+        assert!(xml.contains("code=\""));
+        assert!(!xml.contains("label=\""));
+        assert!(!xml.contains("count=\""));
+    }
+
+    #[test]
+    fn test_name_transform() {
+        let config = GeneratorConfig {
+            name_transform: Some(|name| name.to_uppercase()),
+            ..Default::default()
+        };
+
+        let xml = generate_example("attributes.xsd", &config);
+        assert!(xml.contains("ISBN=\""));
+        assert!(!xml.contains("isbn=\""));
+
+        let xml = generate_example("people.xsd", &config);
+        for name in ["people", "person", "firstname", "lastname", "city"] {
+            assert!(!xml.contains(&format!("<{}>", name)));
+            assert!(xml.contains(&format!("<{}>", name.to_uppercase())));
+        }
+    }
+
+    #[test]
+    fn test_annotate_optional_elements() {
+        let config = GeneratorConfig {
+            annotate: true,
+            optional_probability: Some(1.0),
+            ..Default::default()
+        };
+
+        let xml = generate_example("example.xsd", &config);
+        assert!(xml.contains("<!-- element: note, optional element included -->"));
+        assert!(!xml.contains("xmlgenerator-annotation"));
+
+        let xml = generate_example("example.xsd", &GeneratorConfig::default());
+        assert!(!xml.contains("<!--"));
+
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="entry">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="see--also" type="xs:string" minOccurs="0"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+        assert!(xml.contains("<see--also>"), "{}", xml);
+        for comment in xml.split("<!--").skip(1) {
+            let text = &comment[..comment.find("-->").unwrap()];
+            assert!(!text.contains("--"), "{}", xml);
+        }
+        assert!(xml.contains("<!-- element: see- -also, optional element included -->"));
+    }
+
+    #[test]
+    fn test_annotate_choice_branches() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="order">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="note" type="xs:string"/>
+        <xs:choice>
+          <xs:element name="express" type="xs:string"/>
+          <xs:element name="standard" type="xs:string"/>
+        </xs:choice>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let mut config = GeneratorConfig {
+            annotate: true,
+            ..Default::default()
+        };
+        let marker = " xmlgenerator-annotation=\"0\"";
+        config
+            .value_pools
+            .insert("note".to_string(), vec![marker.to_string()]);
+
+        let mut branches = HashSet::new();
+        for seed in 0..20 {
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                ..config.clone()
+            };
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            assert!(xml.contains(&format!("<note>{}</note>", marker)), "{}", xml);
+
+            let branch = if xml.contains("<express>") {
+                "express"
+            } else {
+                "standard"
+            };
+            let comment = format!(
+                "<!-- element: order, choice branch: {} -->\n\t<{}>",
+                branch, branch
+            );
+            assert!(xml.contains(&comment), "{}", xml);
+            assert_eq!(xml.matches("<!--").count(), 1, "{}", xml);
+            branches.insert(branch);
+        }
+        assert_eq!(branches.len(), 2);
+    }
+
+    #[test]
+    fn test_seed_is_deterministic() {
+        let config = GeneratorConfig {
+            seed: Some(42),
+            ..Default::default()
+        };
+
+        let first = generate_example("example.xsd", &config);
+        let second = generate_example("example.xsd", &config);
+        assert_eq!(first, second);
+
+        let config = GeneratorConfig {
+            seed: Some(43),
+            ..Default::default()
+        };
+
+        let third = generate_example("example.xsd", &config);
+        assert_ne!(first, third);
+    }
+
+    #[test]
+    fn test_all_group_order_follows_seed() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="person">
+    <xs:complexType>
+      <xs:all>
+        <xs:element name="first" type="xs:string"/>
+        <xs:element name="second" type="xs:string"/>
+        <xs:element name="third" type="xs:string"/>
+        <xs:element name="fourth" type="xs:string"/>
+      </xs:all>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let get_order = |seed: u64| {
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                ..Default::default()
+            };
+
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            let mut names = ["first", "second", "third", "fourth"];
+            names.sort_by_key(|x| xml.find(&format!("<{}>", x)).unwrap());
+            names
+        };
+
+        for seed in 0..10 {
+            assert_eq!(get_order(seed), get_order(seed));
+        }
+
+        let mut orders: Vec<_> = (0..10).map(get_order).collect();
+        orders.dedup();
+        assert!(orders.len() > 1);
+    }
+
+    #[test]
+    fn test_sort_all_groups() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="person">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="zone" type="xs:string"/>
+        <xs:element name="details">
+          <xs:complexType>
+            <xs:all>
+              <xs:element name="second" type="xs:string"/>
+              <xs:element name="first" type="xs:string"/>
+              <xs:element name="third" type="xs:string"/>
+            </xs:all>
+          </xs:complexType>
+        </xs:element>
+        <xs:element name="age" type="xs:int"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            sort_all_groups: true,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            let positions: Vec<usize> = ["zone", "details", "first", "second", "third", "age"]
+                .iter()
+                .map(|x| xml.find(&format!("<{}>", x)).unwrap())
+                .collect();
+            assert!(positions.is_sorted(), "{}", xml);
+        }
+    }
+
+    #[test]
+    fn test_estimate_grows_with_repeat_cap() {
+        let filepath = path::absolute("./examples/example.xsd").unwrap();
+        let xsd = std::fs::read_to_string(filepath).unwrap();
+
+        let small = GeneratorConfig {
+            max_repeats: 2,
+            ..Default::default()
+        };
+        let large = GeneratorConfig {
+            max_repeats: 10,
+            ..Default::default()
+        };
+
+        let small_estimate = estimate_max_size(&xsd, &small).unwrap();
+        let large_estimate = estimate_max_size(&xsd, &large).unwrap();
+        assert!(small_estimate < large_estimate);
+
+        for _ in 0..10 {
+            let xml = generate_example("example.xsd", &small);
+            assert!(xml.matches("</").count() + xml.matches("/>").count() <= small_estimate);
+        }
+    }
+
+    #[test]
+    fn test_xsi_type_for_derived_types() {
+        let xml = generate_example("derived_types.xsd", &GeneratorConfig::default());
+        assert!(!xml.contains("xsi:type"));
+        assert!(!xml.contains("xmlns:xsi"));
+
+        let config = GeneratorConfig {
+            xsi_type: true,
+            ..Default::default()
+        };
+
+        let mut found = false;
+        for _ in 0..50 {
+            let xml = generate_example("derived_types.xsd", &config);
+            if xml.contains("xsi:type=\"circle\"") {
+                assert!(xml.contains("<radius>"));
+                assert!(xml.contains("xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\""));
+                found = true;
+                break;
+            }
+        }
+        assert!(found);
+    }
+
+    #[test]
+    fn test_xsi_namespace_only_when_used() {
+        let config = GeneratorConfig {
+            xsi_type: true,
+            ..Default::default()
+        };
+
+        let xml = generate_example("people.xsd", &config);
+        assert!(!xml.contains("xmlns:xsi"));
+
+        let mut found = false;
+        for _ in 0..50 {
+            let xml = generate_example("derived_types.xsd", &config);
+            assert_eq!(xml.contains("xsi:type="), xml.contains("xmlns:xsi="));
+            found |= !xml.contains("xsi:type=");
+        }
+        assert!(found);
+    }
+
+    #[test]
+    fn test_namespace_prefixes() {
+        let xml = generate_example("referenced_note.xsd", &GeneratorConfig::default());
+        assert!(!xml.contains("<n:note"));
+
+        let mut config = GeneratorConfig::default();
+        let namespace_prefixes = &mut config.namespace_prefixes;
+        namespace_prefixes.insert("urn:note".to_string(), "n".to_string());
+        namespace_prefixes.insert("urn:unused".to_string(), "u".to_string());
+
+        let xml = generate_example("referenced_note.xsd", &config);
+        assert!(xml.contains("<n:note xmlns:n=\"urn:note\">"), "{}", xml);
+        assert!(xml.contains("<to>"));
+        assert!(xml.trim_end().ends_with("</n:note>"));
+        assert!(!xml.contains("xmlns:u="));
+
+        config
+            .namespace_prefixes
+            .insert("urn:other".to_string(), "n".to_string());
+        config
+            .namespace_prefixes
+            .insert("urn:reserved".to_string(), "xmlns".to_string());
+        let Err(XMLGeneratorError::InvalidInputError(error)) = config.validate() else {
+            panic!("Expected an invalid input error");
+        };
+        assert!(error.contains("Prefix `n` is used for both `urn:note` and `urn:other`"));
+        assert!(
+            error.contains("Prefix `xmlns` for `urn:reserved` is not a valid namespace prefix")
+        );
+    }
+
+    #[test]
+    fn test_locale_names() {
+        let xml = generate_example("people.xsd", &GeneratorConfig::default());
+        for name in get_values(&xml, "firstname") {
+            assert!(!FR_FR::NAME_FIRST_NAME.contains(&name.as_str()), "{}", name);
+        }
+
+        let config = GeneratorConfig {
+            locale: Some(Locale::FrFr),
+            ..Default::default()
+        };
+
+        let xml = generate_example("people.xsd", &config);
+        let names = get_values(&xml, "firstname");
+        assert!(!names.is_empty());
+        for name in names {
+            assert!(FR_FR::NAME_FIRST_NAME.contains(&name.as_str()), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_xml_lang() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:import namespace="http://www.w3.org/XML/1998/namespace"/>
+  <xs:element name="book">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="title" maxOccurs="unbounded">
+          <xs:complexType>
+            <xs:simpleContent>
+              <xs:extension base="xs:string">
+                <xs:attribute ref="xml:lang"/>
+              </xs:extension>
+            </xs:simpleContent>
+          </xs:complexType>
+        </xs:element>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            attribute_mode: AttributeMode::RequiredOnly,
+            ..Default::default()
+        };
+        let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+        assert!(!xml.contains("xml:lang"), "{}", xml);
+
+        let config = GeneratorConfig {
+            attribute_mode: AttributeMode::RequiredOnly,
+            xml_lang: true,
+            locale: Some(Locale::FrFr),
+            max_repeats: 5,
+            ..Default::default()
+        };
+        let (xml, report) = generate_with_report(xsd, &config).unwrap();
+        assert_well_formed(&xml);
+        assert!(xml.contains("<book>"), "{}", xml);
+        assert!(report.workarounds[0].contains("`book` does not allow `xml:lang`"));
+
+        let open = xsd.replace(
+            "</xs:sequence>\n    </xs:complexType>",
+            "</xs:sequence>\n      <xs:anyAttribute namespace=\"##other\"/>\n    </xs:complexType>",
+        );
+        let (xml, report) = generate_with_report(&open, &config).unwrap();
+        assert!(report.workarounds.is_empty(), "{:?}", report.workarounds);
+        assert!(xml.contains("<book xml:lang=\"fr-FR\">"), "{}", xml);
+        let titles = xml.matches("<title").count();
+        assert!(titles > 0);
+        assert_eq!(
+            xml.matches("<title xml:lang=\"fr-FR\">").count(),
+            titles,
+            "{}",
+            xml
+        );
+    }
+
+    #[test]
+    fn test_name_heuristics() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="contact">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="email" type="xs:string" maxOccurs="unbounded"/>
+        <xs:element name="homePhone" type="xs:string"/>
+        <xs:element name="website_url" type="xs:string"/>
+        <xs:element name="uuid" type="xs:string"/>
+      </xs:sequence>
+      <xs:attribute name="workEmail" type="xs:string" use="required"/>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let xml = generate_xml_from_string_with_config(xsd, &GeneratorConfig::default()).unwrap();
+        assert!(!xml.contains('@'));
+
+        let config = GeneratorConfig {
+            name_heuristics: true,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            let emails = get_values(&xml, "email");
+            assert!(!emails.is_empty());
+            for email in emails {
+                assert!(email.contains('@'), "{}", email);
+            }
+
+            let start = xml.find("workEmail=\"").unwrap();
+            assert!(xml[start..].split('"').nth(1).unwrap().contains('@'));
+            assert!(get_values(&xml, "website_url")[0].starts_with("https://"));
+
+            let uuid = &get_values(&xml, "uuid")[0];
+            let groups: Vec<usize> = uuid.split('-').map(|x| x.len()).collect();
+            assert_eq!(groups, vec![8, 4, 4, 4, 12], "{}", uuid);
+            assert_eq!(&uuid[14..15], "4");
+        }
+    }
+
+    #[test]
+    fn test_max_bytes_budget() {
+        let config = GeneratorConfig {
+            max_repeats: 100,
+            max_bytes: Some(1000),
+            ..Default::default()
+        };
+
+        for seed in 0..10 {
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                ..config.clone()
+            };
+            let xml = generate_example("people.xsd", &config);
+            assert!(xml.len() <= 1000, "{}", xml.len());
+            assert!(xml.len() >= 500, "{}", xml.len());
+            assert!(xml.ends_with("</people>\n"));
+            assert_eq!(
+                xml.matches("<person>").count(),
+                xml.matches("</person>").count()
+            );
+            assert_eq!(
+                xml.matches("<firstname>").count(),
+                xml.matches("</city>").count()
+            );
+        }
+
+        let config = GeneratorConfig {
+            max_bytes: Some(50),
+            ..Default::default()
+        };
+
+        let filepath = path::absolute("./examples/people.xsd").unwrap();
+        let xml = generate_xml_with_config(filepath.into_boxed_path(), &config);
+        assert!(xml.is_err());
+    }
+
+    #[test]
+    fn test_max_bytes_covers_every_write() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:tns="http://example.com/budget"
+           targetNamespace="http://example.com/budget"
+           elementFormDefault="qualified">
+  <xs:complexType name="shape">
+    <xs:sequence>
+      <xs:element name="colour" type="xs:string"/>
+    </xs:sequence>
+    <xs:attribute name="label" type="xs:string"/>
+  </xs:complexType>
+  <xs:complexType name="circle">
+    <xs:complexContent>
+      <xs:extension base="tns:shape">
+        <xs:sequence>
+          <xs:element name="radius" type="xs:double"/>
+        </xs:sequence>
+      </xs:extension>
+    </xs:complexContent>
+  </xs:complexType>
+  <xs:element name="drawing">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="item" type="tns:shape" minOccurs="0" maxOccurs="unbounded"/>
+      </xs:sequence>
+      <xs:anyAttribute namespace="http://www.w3.org/XML/1998/namespace"/>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let mut config = GeneratorConfig {
+            max_repeats: 50,
+            xsi_type: true,
+            xml_lang: true,
+            annotate: true,
+            optional_probability: Some(1.0),
+            ..Default::default()
+        };
+        for name in ["label", "colour"] {
+            let values = vec!["\"Tom & Jerry\" <'s>".to_string()];
+            config.value_pools.insert(name.to_string(), values);
+        }
+
+        for max_bytes in [300, 600, 1200] {
+            for seed in 0..20 {
+                let config = GeneratorConfig {
+                    seed: Some(seed),
+                    max_bytes: Some(max_bytes),
+                    ..config.clone()
+                };
+                let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+                assert!(
+                    xml.len() <= max_bytes,
+                    "{} > {}\n{}",
+                    xml.len(),
+                    max_bytes,
+                    xml
+                );
+                assert!(xml.contains("xml:lang="));
+                assert_well_formed(&xml);
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_total_elements() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="library">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="shelf" maxOccurs="unbounded">
+          <xs:complexType>
+            <xs:sequence>
+              <xs:element name="book" type="xs:string" maxOccurs="unbounded"/>
+            </xs:sequence>
+          </xs:complexType>
+        </xs:element>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let mut config = GeneratorConfig {
+            max_repeats: 20,
+            max_total_elements: Some(25),
+            ..Default::default()
+        };
+
+        let mut largest = 0;
+        for seed in 0..10 {
+            config.seed = Some(seed);
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            let elements = xml.matches('<').count() - xml.matches("</").count() - 1;
+            assert!(elements <= 25, "{}", elements);
+            assert!(xml.ends_with("</library>\n"));
+            largest = largest.max(elements);
+        }
+        assert_eq!(largest, 25);
+
+        config.strict = true;
+        assert!(generate_xml_from_string_with_config(xsd, &config).is_err());
+
+        config.max_total_elements = Some(0);
+        match generate_xml_from_string_with_config(xsd, &config) {
+            Err(XMLGeneratorError::InvalidInputError(error)) => {
+                assert!(error.contains("max_total_elements"), "{}", error)
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_wildcard_process_contents() {
+        let xml = generate_example("wildcards.xsd", &GeneratorConfig::default());
+        let header = xml.find("</header>").unwrap();
+        let body = xml.find("<body>").unwrap();
+        assert!(!xml[header..body].contains("wildcard"));
+        assert_eq!(
+            xml.matches("<wc:wildcard xmlns:wc=\"urn:other\">").count(),
+            1
+        );
+
+        let config = GeneratorConfig {
+            skip_wildcards: true,
+            ..Default::default()
+        };
+
+        let xml = generate_example("wildcards.xsd", &config);
+        assert!(!xml.contains("wildcard"));
+        assert!(xml.contains("<text>"));
+    }
+
+    #[test]
+    fn test_wildcard_positions() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:complexType name="plainType">
+    <xs:sequence>
+      <xs:element name="value" type="xs:string"/>
+    </xs:sequence>
+  </xs:complexType>
+  <xs:complexType name="openType">
+    <xs:sequence>
+      <xs:any processContents="lax"/>
+      <xs:element name="first" type="xs:string"/>
+      <xs:any processContents="lax" namespace="urn:middle"/>
+      <xs:element name="last" type="xs:string"/>
+    </xs:sequence>
+  </xs:complexType>
+  <xs:element name="root">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="item" type="openType"/>
+        <xs:element name="other">
+          <xs:complexType>
+            <xs:sequence>
+              <xs:element name="item" type="plainType"/>
+            </xs:sequence>
+          </xs:complexType>
+        </xs:element>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let xml = generate_xml_from_string_with_config(xsd, &GeneratorConfig::default()).unwrap();
+        let (open, other) = xml.split_once("<other>").unwrap();
+        assert!(!other.contains("wildcard"), "{}", xml);
+
+        let positions: Vec<usize> = [
+            "<wildcard>",
+            "<first>",
+            "<wc:wildcard xmlns:wc=\"urn:middle\">",
+            "<last>",
+        ]
+        .iter()
+        .map(|x| open.find(x).unwrap_or_else(|| panic!("{}", xml)))
+        .collect();
+        assert!(positions.is_sorted(), "{}", xml);
+    }
+
+    #[test]
+    fn test_open_content() {
+        let xml = generate_example("open_content.xsd", &GeneratorConfig::default());
+        assert!(!xml.contains("wildcard"));
+
+        let config = GeneratorConfig {
+            open_content: true,
+            max_repeats: 10,
+            optional_probability: Some(1.0),
+            ..Default::default()
+        };
+
+        let mut interleaved = false;
+        for _ in 0..10 {
+            let xml = generate_example("open_content.xsd", &config);
+            assert_well_formed(&xml);
+            for entry in xml.split("<entry>").skip(1) {
+                let wildcard = entry.find("<wc:wildcard xmlns:wc=\"urn:extra\">");
+                let wildcard = wildcard.unwrap_or_else(|| panic!("{}", entry));
+                assert_eq!(entry.matches("<wc:wildcard").count(), 1, "{}", entry);
+                assert!(entry.contains("<third>"), "{}", entry);
+                interleaved |= wildcard < entry.find("<third>").unwrap();
+            }
+        }
+
+        assert!(interleaved);
+    }
+
+    #[test]
+    fn test_optimizer_steps() {
+        let xml = generate_example("substitution_group.xsd", &GeneratorConfig::default());
+        assert!(xml.contains("<circle>") || xml.contains("<square>"));
+
+        let filepath = path::absolute("./examples/substitution_group.xsd").unwrap();
+        let config = GeneratorConfig {
+            optimizer_steps: OptimizerSteps {
+                convert_dynamic_to_choice: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        match generate_xml_with_config(filepath.into_boxed_path(), &config) {
+            Err(XMLGeneratorError::InvalidInputError(message)) => {
+                assert!(message.contains("ShapeElementType"), "{}", message)
+            }
+            result => panic!("{:?}", result),
+        }
+    }
+}
@@ -2,17 +2,74 @@
 mod tests {
     use std::fs::ReadDir;
     use std::{fs, path};
-    use xmlgenerator::generate_xml;
+    use xmlgenerator::{
+        GeneratorConfig, assert_well_formed, generate_xml, generate_xml_from_string,
+        generate_xml_from_string_with_config, generate_xml_with_config, is_well_formed,
+    };
 
     fn fetch_test_files() -> ReadDir {
         let example_dir = path::absolute("./examples").unwrap();
-        let paths = fs::read_dir(example_dir).unwrap();
+        fs::read_dir(example_dir).unwrap()
+    }
+
+    const EXPECTED_ROOTS: [(&str, &str); 32] = [
+        ("alternatives.xsd", "message"),
+        ("assertions.xsd", "range"),
+        ("attribute_extension.xsd", "item"),
+        ("attributes.xsd", "book"),
+        ("complex_in_complex.xsd", "person"),
+        ("complex_in_complex2.xsd", "person"),
+        ("complex_in_complex_norefs.xsd", "person"),
+        ("custom_prefix.xsd", "person"),
+        ("default_attributes.xsd", "catalog"),
+        ("default_namespace.xsd", "person"),
+        ("derived_types.xsd", "drawing"),
+        ("example.xsd", "shiporder"),
+        ("nested_optional_group.xsd", "route"),
+        ("open_content.xsd", "journal"),
+        ("optional_group.xsd", "contacts"),
+        ("people.xsd", "people"),
+        ("recursive_optional.xsd", "node"),
+        ("ref_wrapper.xsd", "library"),
+        ("referenced_note.xsd", "note"),
+        ("repeated_choice.xsd", "feed"),
+        ("repeated_group.xsd", "map"),
+        ("repeated_refs.xsd", "basket"),
+        ("restricted_content.xsd", "address"),
+        ("root_ref.xsd", "note"),
+        ("separate_types.xsd", "film"),
+        ("side_cycle.xsd", "library"),
+        ("simple.xsd", "person"),
+        ("simple_refs.xsd", "person"),
+        ("simple_refs2.xsd", "person"),
+        ("substitution_group.xsd", "drawing"),
+        ("version_control.xsd", "config"),
+        ("wildcards.xsd", "envelope"),
+    ];
+
+    fn get_root_name(xml: &str) -> &str {
+        let mut rest = xml;
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start + 1..];
+            if !rest.starts_with(['?', '!']) {
+                break;
+            }
+        }
 
-        paths
+        let end = rest.find(|x: char| x.is_whitespace() || x == '>' || x == '/');
+        let name = &rest[..end.unwrap()];
+        name.split_once(':').map_or(name, |(_, x)| x)
     }
 
-    fn check_result(result: String) {
+    fn check_result(filename: &str, result: String) {
         println!("{}", result);
+        assert_well_formed(&result);
+
+        let expected = EXPECTED_ROOTS.iter().find(|(x, _)| *x == filename);
+        let Some((_, root)) = expected else {
+            panic!("No expected root for {}", filename);
+        };
+        assert_eq!(get_root_name(&result), *root, "{}", filename);
     }
 
     #[test]
@@ -22,12 +79,514 @@ mod tests {
         for file in files {
             let filepath = file.unwrap().path();
             println!("{}", filepath.display());
+            let filename = filepath.file_name().unwrap().to_string_lossy().to_string();
             let xml = generate_xml(filepath.into_boxed_path());
 
             match xml {
-                Ok(result) => check_result(result),
+                Ok(result) => check_result(&filename, result),
+                Err(err) => panic!("{:?}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_namespace_declarations() {
+        for file in fetch_test_files() {
+            let filepath = file.unwrap().path();
+            let xsd = fs::read_to_string(&filepath).unwrap();
+            if ["targetNamespace", "xs:import", "xs:any"]
+                .iter()
+                .any(|x| xsd.contains(x))
+            {
+                continue;
+            }
+
+            let xml = generate_xml(filepath.clone().into_boxed_path()).unwrap();
+            assert!(!xml.contains("xmlns"), "{}: {}", filepath.display(), xml);
+        }
+
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:unused="urn:unused"
+           elementFormDefault="qualified"
+           attributeFormDefault="qualified">
+  <xs:element name="memo">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="text" type="xs:string"/>
+      </xs:sequence>
+      <xs:attribute name="id" type="xs:string" use="required"/>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let xml = generate_xml_from_string(xsd).unwrap();
+        assert!(xml.contains("<memo id=\""), "{}", xml);
+        assert!(xml.contains("<text>"), "{}", xml);
+        assert!(!xml.contains("xmlns"), "{}", xml);
+    }
+
+    #[test]
+    fn test_is_well_formed() {
+        let filepath = path::absolute("./examples/simple.xsd").unwrap();
+        let xml = generate_xml(filepath.into_boxed_path()).unwrap();
+        assert!(is_well_formed(&xml));
+        assert!(is_well_formed("<a><b>x &amp; y</b><c/></a>"));
+
+        for xml in [
+            "",
+            "<a><b></a>",
+            "<a></a><b></b>",
+            "<a>x & y</a>",
+            "text<a></a>",
+            "<a>",
+        ] {
+            assert!(!is_well_formed(xml), "{}", xml);
+        }
+    }
+
+    #[test]
+    fn test_optional_recursion() {
+        let filepath = path::absolute("./examples/recursive_optional.xsd").unwrap();
+        let config = GeneratorConfig {
+            max_depth: 3,
+            max_repeats: 1,
+            optional_probability: Some(1.0),
+            ..Default::default()
+        };
+
+        let xml = generate_xml_with_config(filepath.into_boxed_path(), &config);
+
+        match xml {
+            Ok(result) => assert_eq!(result.matches("<label>").count(), 4),
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_repeated_reference() {
+        let filepath = path::absolute("./examples/repeated_refs.xsd").unwrap();
+        let config = GeneratorConfig {
+            max_repeats: 4,
+            ..Default::default()
+        };
+
+        for _ in 0..20 {
+            let xml = generate_xml_with_config(filepath.clone().into_boxed_path(), &config);
+
+            match xml {
+                Ok(result) => {
+                    let items = result.matches("<item>").count();
+                    assert!(items >= 2);
+                    assert!(items <= 4);
+                }
                 Err(err) => panic!("{:?}", err),
             }
         }
     }
+
+    #[test]
+    fn test_xsd_namespace_prefixes() {
+        for filename in ["custom_prefix.xsd", "default_namespace.xsd"] {
+            let filepath = path::absolute("./examples").unwrap().join(filename);
+            let xml = generate_xml(filepath.into_boxed_path());
+
+            match xml {
+                Ok(result) => {
+                    assert!(result.contains("<name>"));
+                    assert!(result.contains("<age>"));
+                }
+                Err(err) => panic!("{:?}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn test_restriction_removes_optional_element() {
+        let filepath = path::absolute("./examples/restricted_content.xsd").unwrap();
+
+        for _ in 0..10 {
+            let xml = generate_xml(filepath.clone().into_boxed_path());
+
+            match xml {
+                Ok(result) => {
+                    assert!(result.contains("<address>"));
+                    assert!(result.contains("<street>"));
+                    assert!(result.contains("<city>"));
+                    assert!(!result.contains("<suite>"));
+                }
+                Err(err) => panic!("{:?}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn test_root_reference() {
+        let filepath = path::absolute("./examples/root_ref.xsd").unwrap();
+        let xml = generate_xml(filepath.into_boxed_path());
+
+        match xml {
+            Ok(result) => {
+                assert!(result.contains("\n<n:note xmlns:n=\"urn:note\">\n"));
+                assert!(result.contains("<to>"));
+                assert!(result.ends_with("</n:note>\n"));
+            }
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_ref_wrapped_root() {
+        let filepath = path::absolute("./examples/ref_wrapper.xsd").unwrap();
+        let xml = generate_xml(filepath.into_boxed_path());
+
+        match xml {
+            Ok(result) => {
+                assert!(result.contains("\n<library>\n\t<shelf>\n\t\t<book>\n"));
+                assert!(result.contains("<title>"));
+                assert!(result.ends_with("</library>\n"));
+            }
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_default_alternative() {
+        let filepath = path::absolute("./examples/alternatives.xsd").unwrap();
+        let xml = generate_xml(filepath.into_boxed_path());
+
+        match xml {
+            Ok(result) => {
+                assert!(result.contains("\n<message"));
+                assert!(result.contains("<body>"));
+                assert!(result.contains("<deadline>"));
+            }
+            Err(err) => panic!("{:?}", err),
+        }
+
+        let xsd = fs::read_to_string("./examples/alternatives.xsd").unwrap();
+        let xsd = xsd.replace("<xs:alternative type=\"urgentMessage\"/>", "");
+        let result = generate_xml_from_string(&xsd).unwrap();
+        assert!(result.contains("<body>"));
+        assert!(!result.contains("<deadline>"));
+    }
+
+    #[test]
+    fn test_repeated_group_reference() {
+        let mut counts = vec![];
+        for seed in 0..20 {
+            let filepath = path::absolute("./examples/repeated_group.xsd").unwrap();
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                ..Default::default()
+            };
+            let result = generate_xml_with_config(filepath.into_boxed_path(), &config).unwrap();
+
+            assert!(!result.contains("Content"));
+            let keys = result.matches("<key>").count();
+            assert_eq!(keys, result.matches("<value>").count());
+            assert!((1..=3).contains(&keys));
+            assert!(result.find("<name>").unwrap() < result.find("<key>").unwrap());
+            assert!(result.find("<key>").unwrap() < result.find("<value>").unwrap());
+            counts.push(keys);
+        }
+
+        assert!(counts.contains(&3));
+    }
+
+    #[test]
+    fn test_unbounded_group_reference() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:group name="entryGroup">
+    <xs:sequence>
+      <xs:element name="key" type="xs:string"/>
+      <xs:element name="value" type="xs:int"/>
+    </xs:sequence>
+  </xs:group>
+  <xs:element name="map">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:group ref="entryGroup" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let mut counts = vec![];
+        for seed in 0..20 {
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                max_repeats: 5,
+                ..Default::default()
+            };
+            let result = generate_xml_from_string_with_config(xsd, &config).unwrap();
+
+            let tags: Vec<&str> = result
+                .split('<')
+                .filter_map(|x| x.split_once('>').map(|(tag, _)| tag))
+                .filter(|x| *x == "key" || *x == "value")
+                .collect();
+            assert_eq!(tags.len() % 2, 0, "{}", result);
+            assert!(tags.chunks(2).all(|x| x == ["key", "value"]), "{}", result);
+
+            let blocks = tags.len() / 2;
+            assert!((1..=5).contains(&blocks), "{}", result);
+            counts.push(blocks);
+        }
+
+        assert!(counts.contains(&5));
+    }
+
+    #[test]
+    fn test_optional_nested_group() {
+        let (mut with_phone, mut without_phone) = (0, 0);
+        for seed in 0..20 {
+            let filepath = path::absolute("./examples/optional_group.xsd").unwrap();
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                optional_probability: Some(0.5),
+                ..Default::default()
+            };
+            let result = generate_xml_with_config(filepath.into_boxed_path(), &config).unwrap();
+
+            assert!(!result.contains("Content"));
+            for contact in result.split("<contact>").skip(1) {
+                assert!(contact.contains("<name>"));
+                assert!(contact.contains("<note>"));
+                assert_eq!(contact.contains("<phone>"), contact.contains("<extension>"));
+                match contact.find("<phone>") {
+                    Some(phone) => {
+                        let extension = contact.find("<extension>").unwrap();
+                        assert!(contact.find("<name>").unwrap() < phone);
+                        assert!(phone < extension);
+                        assert!(extension < contact.find("<note>").unwrap());
+                        with_phone += 1;
+                    }
+                    None => without_phone += 1,
+                }
+            }
+        }
+
+        assert!(with_phone > 0);
+        assert!(without_phone > 0);
+    }
+
+    #[test]
+    fn test_optional_group_in_repeated_group() {
+        let (mut timed, mut untimed) = (0, 0);
+        for seed in 0..20 {
+            let filepath = path::absolute("./examples/nested_optional_group.xsd").unwrap();
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                optional_probability: Some(0.5),
+                ..Default::default()
+            };
+            let result = generate_xml_with_config(filepath.into_boxed_path(), &config).unwrap();
+
+            assert!(result.contains("<origin>"));
+            assert!(result.contains("<destination>"));
+            let stops: Vec<&str> = result.split("<stop>").skip(1).collect();
+            assert!((1..=2).contains(&stops.len()));
+            for stop in stops {
+                assert_eq!(stop.contains("<arrival>"), stop.contains("<departure>"));
+                match stop.find("<arrival>") {
+                    Some(arrival) => {
+                        assert!(arrival < stop.find("<departure>").unwrap());
+                        timed += 1;
+                    }
+                    None => untimed += 1,
+                }
+            }
+        }
+
+        assert!(timed > 0);
+        assert!(untimed > 0);
+    }
+
+    #[test]
+    fn test_repeated_choice() {
+        let mut mixed = 0;
+        for seed in 0..20 {
+            let filepath = path::absolute("./examples/repeated_choice.xsd").unwrap();
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                ..Default::default()
+            };
+            let result = generate_xml_with_config(filepath.into_boxed_path(), &config).unwrap();
+
+            assert!(!result.contains("Content"));
+            assert!(result.find("<title>").unwrap() < result.find("</title>").unwrap());
+            let branches = result.matches("<post>").count() + result.matches("<link>").count();
+            assert!((1..=6).contains(&branches));
+            if result.contains("<post>") && result.contains("<link>") {
+                mixed += 1;
+            }
+        }
+
+        assert!(mixed > 0);
+    }
+
+    #[test]
+    fn test_choice_weights() {
+        let filepath = path::absolute("./examples/repeated_choice.xsd").unwrap();
+        let mut config = GeneratorConfig::default();
+        let weights = &mut config.choice_weights;
+        weights.insert(("feed".to_string(), "post".to_string()), 9.0);
+        weights.insert(("feed".to_string(), "link".to_string()), 1.0);
+
+        let (mut posts, mut links) = (0, 0);
+        for seed in 0..100 {
+            config.seed = Some(seed);
+            let filepath = filepath.clone().into_boxed_path();
+            let result = generate_xml_with_config(filepath, &config).unwrap();
+            posts += result.matches("<post>").count();
+            links += result.matches("<link>").count();
+        }
+
+        let ratio = posts as f64 / (posts + links) as f64;
+        assert!((0.8..0.97).contains(&ratio), "{}", ratio);
+
+        config
+            .choice_weights
+            .insert(("feed".to_string(), "link".to_string()), 0.0);
+        for seed in 0..20 {
+            config.seed = Some(seed);
+            let filepath = filepath.clone().into_boxed_path();
+            let result = generate_xml_with_config(filepath, &config).unwrap();
+            assert!(result.contains("<post>"));
+            assert!(!result.contains("<link>"));
+        }
+    }
+
+    #[test]
+    fn test_abstract_element_substitutes() {
+        let (mut circles, mut squares) = (0, 0);
+        for seed in 0..10 {
+            let filepath = path::absolute("./examples/substitution_group.xsd").unwrap();
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                ..Default::default()
+            };
+            let result = generate_xml_with_config(filepath.into_boxed_path(), &config).unwrap();
+
+            assert!(!result.contains("<shape>"));
+            assert_eq!(
+                result.matches("<colour>").count(),
+                result.matches("<circle>").count() + result.matches("<square>").count()
+            );
+            assert_eq!(
+                result.matches("<radius>").count(),
+                result.matches("<circle>").count()
+            );
+            circles += result.matches("<circle>").count();
+            squares += result.matches("<square>").count();
+        }
+
+        assert!(circles > 0);
+        assert!(squares > 0);
+    }
+
+    #[test]
+    fn test_attribute_only_extension() {
+        let filepath = path::absolute("./examples/attribute_extension.xsd").unwrap();
+        let xml = generate_xml(filepath.into_boxed_path());
+
+        match xml {
+            Ok(result) => {
+                assert!(result.contains("<item id=\""));
+                assert!(result.find(" id=\"").unwrap() < result.find(" tag=\"").unwrap());
+                assert!(result.contains("<title>"));
+                assert!(result.contains("<count>"));
+            }
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_version_control_attributes_are_ignored() {
+        let filepath = path::absolute("./examples/version_control.xsd").unwrap();
+        let xml = generate_xml(filepath.into_boxed_path());
+
+        match xml {
+            Ok(result) => {
+                assert!(result.contains("<config id=\""));
+                assert!(result.contains("<name>"));
+                assert!(
+                    result.contains("<mode>fast</mode>") || result.contains("<mode>safe</mode>")
+                );
+                assert!(result.contains("<legacy>"));
+                assert!(!result.contains("minVersion"));
+            }
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_assertions_are_ignored() {
+        let filepath = path::absolute("./examples/assertions.xsd").unwrap();
+        let xml = generate_xml(filepath.into_boxed_path());
+
+        match xml {
+            Ok(result) => {
+                let low = result.split("<low>").nth(1).unwrap();
+                let low: i32 = low.split("</low>").next().unwrap().parse().unwrap();
+                assert!(low >= 0);
+                assert!(result.contains("<high>"));
+                assert!(result.contains("<label>"));
+            }
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_default_attributes() {
+        let filepath = path::absolute("./examples/default_attributes.xsd").unwrap();
+        let xml = generate_xml(filepath.into_boxed_path());
+
+        match xml {
+            Ok(result) => {
+                assert!(result.contains("<item id=\""));
+                assert!(!result.contains("<item>"));
+                assert!(result.contains("<note>"));
+            }
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_default_attributes_on_derived_types() {
+        let filepath = path::absolute("./examples/default_attributes.xsd").unwrap();
+        let xsd = fs::read_to_string(filepath).unwrap();
+        let derived = r#"<xs:complexType name="bookType">
+    <xs:complexContent>
+      <xs:extension base="itemType">
+        <xs:sequence>
+          <xs:element name="isbn" type="xs:string"/>
+        </xs:sequence>
+      </xs:extension>
+    </xs:complexContent>
+  </xs:complexType>
+  <xs:complexType name="priceType">
+    <xs:simpleContent>
+      <xs:extension base="xs:decimal"/>
+    </xs:simpleContent>
+  </xs:complexType>
+  <xs:element name="catalog">"#;
+        let xsd = xsd
+            .replace("<xs:element name=\"catalog\">", derived)
+            .replace("type=\"itemType\"", "type=\"bookType\"")
+            .replace(
+                "<xs:element name=\"note\" type=\"plainType\"/>",
+                r#"<xs:element name="note" type="plainType"/>
+        <xs:element name="price" type="priceType"/>"#,
+            );
+
+        let xml = generate_xml_from_string(&xsd).unwrap();
+        assert_well_formed(&xml);
+        assert!(xml.contains("<item id=\""), "{}", xml);
+        assert!(xml.contains("<isbn>"), "{}", xml);
+        assert!(xml.contains("<price id=\""), "{}", xml);
+        assert!(xml.contains("<note>"), "{}", xml);
+    }
 }
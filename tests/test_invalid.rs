@@ -2,22 +2,20 @@
 mod tests {
     use std::fs::ReadDir;
     use std::{fs, path};
-    use std::path::PathBuf;
-    use xmlgenerator::{generate_xml, generate_xml_from_string, XMLGeneratorError};
+    use std::path::Path;
+    use xmlgenerator::{generate_xml, generate_xml_from_string, validate_schema, XMLGeneratorError};
 
     fn fetch_test_files() -> ReadDir {
         let example_dir = path::absolute("./invalid").unwrap();
-        let paths = fs::read_dir(example_dir).unwrap();
-
-        paths
+        fs::read_dir(example_dir).unwrap()
     }
 
     fn check_error(error_string: &String, expected_error: &String) {
         assert_eq!(error_string, expected_error);
     }
 
-    fn test_xml(filepath: &PathBuf, expected: String) {
-        let xml = generate_xml(filepath.clone().into_boxed_path());
+    fn test_xml(filepath: &Path, expected: String) {
+        let xml = generate_xml(filepath.to_path_buf().into_boxed_path());
 
         assert!(xml.is_err());
         match xml.unwrap_err() {
@@ -26,6 +24,7 @@ mod tests {
             XMLGeneratorError::InvalidInputError(error) => check_error(&error, &expected),
             XMLGeneratorError::XMLGenerationError(error) => panic!("XML generation error: {}", error),
             XMLGeneratorError::StringConversionError(error) => panic!("String conversion error: {}", error),
+            XMLGeneratorError::IOError(error) => panic!("IO error: {}", error),
         }
     }
 
@@ -44,6 +43,47 @@ mod tests {
     fn test_invalid_file() {
         test_error("recursive.xsd", "No independent structs found");
         test_error("two_roots.xsd", "Multiple independent structs found!");
+        test_error(
+            "name_and_ref.xsd",
+            "Element `entry` declares both a name and a ref to `item`",
+        );
+        test_error(
+            "abstract_element.xsd",
+            "Abstract element `shape` has no substitution group members",
+        );
+        test_error(
+            "list_type.xsd",
+            "Unnamed fields are not supported, found in `SizesType`",
+        );
+    }
+
+    #[test]
+    fn test_validate_schema() {
+        let example_dir = path::absolute("./examples").unwrap();
+        for file in fs::read_dir(example_dir).unwrap() {
+            let filepath = file.unwrap().path();
+            let xsd = fs::read_to_string(&filepath).unwrap();
+            if xsd.contains("schemaLocation") {
+                continue;
+            }
+
+            if let Err(err) = validate_schema(&xsd) {
+                panic!("{}: {:?}", filepath.display(), err);
+            }
+        }
+
+        for (filename, expected) in [
+            ("recursive.xsd", "No independent structs found"),
+            ("two_roots.xsd", "Multiple independent structs found!"),
+            ("name_and_ref.xsd", "Element `entry` declares both a name and a ref to `item`"),
+            ("union_type.xsd", "No generator for type `UType` of required element `v`"),
+        ] {
+            let xsd = fs::read_to_string(Path::new("./invalid").join(filename)).unwrap();
+            match validate_schema(&xsd) {
+                Err(XMLGeneratorError::InvalidInputError(error)) => assert_eq!(error, expected),
+                other => panic!("{}: {:?}", filename, other),
+            }
+        }
     }
 
     #[test]
@@ -59,6 +99,7 @@ mod tests {
             XMLGeneratorError::InvalidInputError(_) => panic!("Invalid input error"),
             XMLGeneratorError::XMLGenerationError(_) => panic!("XML generation error"),
             XMLGeneratorError::StringConversionError(_) => panic!("String conversion error"),
+            XMLGeneratorError::IOError(_) => panic!("IO error"),
         }
     }
 }
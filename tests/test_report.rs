@@ -0,0 +1,342 @@
+#[cfg(test)]
+mod tests {
+    use xmlgenerator::{
+        GenerationMode, GeneratorConfig, XMLGenerator, XMLGeneratorError, generate_with_coverage,
+        generate_with_report,
+    };
+
+    const UNION_SCHEMA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="sizeType">
+    <xs:union memberTypes="xs:int xs:boolean"/>
+  </xs:simpleType>
+  <xs:element name="item">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="name" type="xs:string"/>
+        <xs:element name="size" type="sizeType"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+    #[test]
+    fn test_report_flags_fallbacks() {
+        let config = GeneratorConfig::default();
+        let (xml, report) = generate_with_report(UNION_SCHEMA, &config).unwrap();
+
+        assert!(xml.contains("<name>"));
+        assert!(!xml.contains("<size>"));
+        assert!(report.used_fallbacks);
+        assert_eq!(report.workarounds.len(), 1);
+        assert!(report.workarounds[0].contains("`size`"));
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_fallbacks() {
+        let config = GeneratorConfig {
+            strict: true,
+            ..Default::default()
+        };
+
+        let result = generate_with_report(UNION_SCHEMA, &config);
+        match result {
+            Err(XMLGeneratorError::XMLGenerationError(err)) => assert!(err.contains("`size`")),
+            _ => panic!("Expected a strict mode error"),
+        }
+
+        let lenient = GeneratorConfig::default();
+        assert!(generate_with_report(UNION_SCHEMA, &lenient).is_ok());
+
+        let xsd = UNION_SCHEMA.replace("sizeType\"/>", "xs:int\"/>");
+        assert!(generate_with_report(&xsd, &config).is_ok());
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_ignored_constructs() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns="http://example.com/report"
+           targetNamespace="http://example.com/report">
+  <xs:simpleType name="recent">
+    <xs:restriction base="xs:date">
+      <xs:minInclusive value="2020-01-01"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="item">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="since" type="recent"/>
+        <xs:element name="name" type="xs:string" form="qualified"/>
+        <xs:element name="tag" type="xs:string" maxOccurs="3"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let strict = GeneratorConfig {
+            strict: true,
+            ..Default::default()
+        };
+        let lenient = GeneratorConfig::default();
+
+        let (_, report) = generate_with_report(xsd, &lenient).unwrap();
+        assert_eq!(report.workarounds.len(), 2, "{:?}", report.workarounds);
+        assert!(report.workarounds[0].contains("`minInclusive` facet `2020-01-01`"));
+        assert!(report.workarounds[1].contains("`form` attribute of `name`"));
+
+        let xsd = xsd.replace(" form=\"qualified\"", "");
+        match generate_with_report(&xsd, &strict) {
+            Err(XMLGeneratorError::XMLGenerationError(err)) => {
+                assert!(err.contains("minInclusive"), "{}", err)
+            }
+            other => panic!("Expected a strict mode error, got {:?}", other),
+        }
+
+        let xsd = xsd.replace("type=\"recent\"", "type=\"xs:date\"");
+        assert!(generate_with_report(&xsd, &strict).is_ok());
+
+        let xsd = xsd.replace("maxOccurs=\"3\"", "maxOccurs=\"unbounded\"");
+        let (_, report) = generate_with_report(&xsd, &lenient).unwrap();
+        assert!(report.workarounds.is_empty(), "{:?}", report.workarounds);
+        assert!(
+            report.warnings[0].contains("capped"),
+            "{:?}",
+            report.warnings
+        );
+
+        match generate_with_report(&xsd, &strict) {
+            Err(XMLGeneratorError::XMLGenerationError(err)) => {
+                assert!(err.contains("Unbounded element `tag`"), "{}", err)
+            }
+            other => panic!("Expected a strict mode error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_required_attribute_without_generator() {
+        let required = UNION_SCHEMA.replace(
+            "</xs:sequence>",
+            "</xs:sequence>\n      <xs:attribute name=\"width\" type=\"sizeType\" use=\"required\"/>",
+        );
+
+        let config = GeneratorConfig::default();
+        match generate_with_report(&required, &config) {
+            Err(XMLGeneratorError::XMLGenerationError(err)) => {
+                assert!(err.contains("required attribute `width`"), "{}", err)
+            }
+            other => panic!("Expected a generation error, got {:?}", other),
+        }
+
+        let optional = required.replace(" use=\"required\"", "");
+        let config = GeneratorConfig {
+            optional_probability: Some(1.0),
+            ..Default::default()
+        };
+        let (xml, report) = generate_with_report(&optional, &config).unwrap();
+        assert!(!xml.contains("width="), "{}", xml);
+        assert!(report.workarounds.iter().any(|x| x.contains("`width`")));
+    }
+
+    #[test]
+    fn test_report_without_fallbacks() {
+        let xsd = UNION_SCHEMA.replace("sizeType\"/>", "xs:int\"/>");
+        let config = GeneratorConfig::default();
+        let (xml, report) = generate_with_report(&xsd, &config).unwrap();
+
+        assert!(xml.contains("<size>"));
+        assert!(!report.used_fallbacks);
+        assert!(report.workarounds.is_empty());
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_report_warns_about_capped_elements() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="list">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="entry" type="xs:string" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 2,
+            ..Default::default()
+        };
+
+        let (xml, report) = generate_with_report(xsd, &config).unwrap();
+        assert!(xml.matches("<entry>").count() <= 2);
+        assert!(!report.used_fallbacks);
+        assert_eq!(
+            report.warnings,
+            vec!["Unbounded element `entry` capped at 2 repetitions".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_invalid_mode_reports_violation() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="ageType">
+    <xs:restriction base="xs:int">
+      <xs:minInclusive value="0"/>
+      <xs:maxInclusive value="120"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="person">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="name" type="xs:string"/>
+        <xs:element name="age" type="ageType"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let (_, report) = generate_with_report(xsd, &GeneratorConfig::default()).unwrap();
+        assert_eq!(report.violation, None);
+
+        let config = GeneratorConfig {
+            mode: GenerationMode::Invalid,
+            ..Default::default()
+        };
+
+        for _ in 0..20 {
+            let (xml, report) = generate_with_report(xsd, &config).unwrap();
+            let violation = report.violation.unwrap();
+            let age = xml
+                .split("<age>")
+                .nth(1)
+                .map(|x| x[..x.find("</age>").unwrap()].parse::<i32>().unwrap());
+
+            match violation.as_str() {
+                "Left out required element `name`" => {
+                    assert!(!xml.contains("<name>"));
+                    assert!((0..=120).contains(&age.unwrap()));
+                }
+                "Left out required element `age`" => {
+                    assert!(xml.contains("<name>"));
+                    assert_eq!(age, None);
+                }
+                "Value `121` of `age` is outside its bounds" => {
+                    assert!(xml.contains("<name>"));
+                    assert_eq!(age, Some(121));
+                }
+                _ => panic!("{}", violation),
+            }
+        }
+    }
+
+    #[test]
+    fn test_coverage_counts_optional_elements() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="book">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="title" type="xs:string"/>
+        <xs:element name="note" type="xs:string" minOccurs="0"/>
+        <xs:choice>
+          <xs:element name="isbn" type="xs:string"/>
+          <xs:element name="issn" type="xs:string"/>
+        </xs:choice>
+      </xs:sequence>
+      <xs:attribute name="lang" type="xs:string"/>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let key = |x: &str| ("book".to_string(), x.to_string());
+        let (mut with_note, mut without_note) = (0, 0);
+        for seed in 0..20 {
+            let generator = XMLGenerator::builder()
+                .seed(seed)
+                .optional_probability(0.5)
+                .build();
+            let (xml, coverage) = generator.generate_with_coverage(xsd).unwrap();
+            let particles = &coverage.particles;
+
+            let notes = xml.matches("<note>").count();
+            assert_eq!(particles[&key("note")], notes, "{}", xml);
+            assert_eq!(particles[&key("title")], 1);
+            assert_eq!(particles[&key("isbn")] + particles[&key("issn")], 1);
+            assert_eq!(particles[&key("isbn")], xml.matches("<isbn>").count());
+            assert_eq!(particles[&key("lang")], xml.matches(" lang=").count());
+
+            match notes {
+                0 => without_note += 1,
+                _ => with_note += 1,
+            }
+        }
+
+        assert!(with_note > 0);
+        assert!(without_note > 0);
+    }
+
+    #[test]
+    fn test_coverage_is_keyed_by_definition() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:complexType name="addressType">
+    <xs:sequence>
+      <xs:element name="street" type="xs:string"/>
+      <xs:element name="note" type="xs:string" minOccurs="0"/>
+    </xs:sequence>
+  </xs:complexType>
+  <xs:element name="contact">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="home" type="addressType"/>
+        <xs:element name="work" type="addressType"/>
+        <xs:element name="phone">
+          <xs:complexType>
+            <xs:sequence>
+              <xs:element name="note" type="xs:string" minOccurs="0"/>
+            </xs:sequence>
+          </xs:complexType>
+        </xs:element>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let key = |x: &str, y: &str| (x.to_string(), y.to_string());
+        for seed in 0..10 {
+            let config = GeneratorConfig {
+                seed: Some(seed),
+                ..Default::default()
+            };
+            let (xml, coverage) = generate_with_coverage(xsd, &config).unwrap();
+            let particles = &coverage.particles;
+
+            let phone_notes = xml
+                .split("<phone")
+                .nth(1)
+                .unwrap()
+                .matches("<note>")
+                .count();
+            let notes = xml.matches("<note>").count();
+
+            assert_eq!(particles[&key("contact", "home")], 1);
+            assert_eq!(particles[&key("addressType", "street")], 2);
+            assert_eq!(particles[&key("addressType", "note")], notes - phone_notes);
+            assert_eq!(particles[&key("contact/phone", "note")], phone_notes);
+            assert!(!particles.contains_key(&key("home", "street")));
+        }
+    }
+
+    #[test]
+    fn test_coverage_skips_union_members() {
+        let (_, coverage) =
+            generate_with_coverage(UNION_SCHEMA, &GeneratorConfig::default()).unwrap();
+        let particles = &coverage.particles;
+
+        assert_eq!(particles.len(), 2, "{:?}", particles);
+        assert_eq!(particles[&("item".to_string(), "name".to_string())], 1);
+        assert_eq!(particles[&("item".to_string(), "size".to_string())], 0);
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path};
+    use xmlgenerator::{
+        GeneratorConfig, generate_all_roots, generate_all_roots_to_dir, generate_detailed,
+        generate_xml_from_string, list_root_candidates,
+    };
+
+    fn read_two_roots() -> String {
+        let filepath = path::absolute("./invalid/two_roots.xsd").unwrap();
+        fs::read_to_string(filepath).unwrap()
+    }
+
+    #[test]
+    fn test_generate_all_roots() {
+        let documents = generate_all_roots(&read_two_roots()).unwrap();
+
+        assert_eq!(documents.len(), 2);
+        for (name, xml) in documents.iter() {
+            assert!(["person", "house"].contains(&name.as_str()), "{}", name);
+            assert!(xml.contains(&format!("<{}>", name)), "{}", xml);
+        }
+    }
+
+    #[test]
+    fn test_list_root_candidates() {
+        let mut roots = list_root_candidates(&read_two_roots()).unwrap();
+        roots.sort();
+        assert_eq!(roots, vec!["house", "person"]);
+
+        let filepath = path::absolute("./examples/side_cycle.xsd").unwrap();
+        let xsd = fs::read_to_string(filepath).unwrap();
+        assert_eq!(list_root_candidates(&xsd).unwrap(), vec!["library"]);
+
+        let filepath = path::absolute("./invalid/recursive.xsd").unwrap();
+        let xsd = fs::read_to_string(filepath).unwrap();
+        assert!(list_root_candidates(&xsd).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_generate_detailed() {
+        for (filename, root) in [("side_cycle.xsd", "library"), ("simple.xsd", "person")] {
+            let filepath = path::absolute("./examples").unwrap().join(filename);
+            let xsd = fs::read_to_string(filepath).unwrap();
+
+            let document = generate_detailed(&xsd, &GeneratorConfig::default()).unwrap();
+            assert_eq!(document.root_name, root);
+            let tag = format!("?>\n<{}>", root);
+            assert!(document.xml.contains(&tag), "{}", document.xml);
+        }
+    }
+
+    #[test]
+    fn test_generate_all_roots_to_dir() {
+        let dir = env::temp_dir().join(format!("xmlgenerator-roots-{}", std::process::id()));
+        generate_all_roots_to_dir(&read_two_roots(), &dir).unwrap();
+
+        let person = fs::read_to_string(dir.join("person.xml")).unwrap();
+        let house = fs::read_to_string(dir.join("house.xml")).unwrap();
+        assert!(person.contains("<name>"));
+        assert!(house.contains("<address>"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_root_beside_cycle() {
+        let filepath = path::absolute("./examples/side_cycle.xsd").unwrap();
+        let xsd = fs::read_to_string(filepath).unwrap();
+        let isolated = xsd.replace("<xs:element ref=\"library\" minOccurs=\"0\"/>", "");
+
+        for schema in [xsd, isolated] {
+            let xml = generate_xml_from_string(&schema).unwrap();
+            assert!(xml.contains("<library>"), "{}", xml);
+            assert!(xml.contains("<title>"), "{}", xml);
+            assert!(!xml.contains("<ping>"), "{}", xml);
+
+            let documents = generate_all_roots(&schema).unwrap();
+            assert_eq!(documents.len(), 1);
+            assert_eq!(documents[0].0, "library");
+        }
+    }
+}
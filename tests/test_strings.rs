@@ -0,0 +1,408 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::get_values;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+    use std::{env, fs};
+    use xmlgenerator::{
+        GeneratorConfig, XMLGeneratorError, generate_xml_bytes, generate_xml_from_gz,
+        generate_xml_from_string_with_config, generate_xml_from_strings, generate_xml_to_file,
+    };
+
+    const MAIN_SCHEMA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:addr="urn:address"
+           targetNamespace="urn:person">
+  <xs:import namespace="urn:address" schemaLocation="address.xsd"/>
+  <xs:element name="person">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="name" type="xs:string"/>
+        <xs:element name="home" type="addr:addresstype"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+    const ADDRESS_SCHEMA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           targetNamespace="urn:address">
+  <xs:complexType name="addresstype">
+    <xs:sequence>
+      <xs:element name="street" type="xs:string"/>
+      <xs:element name="city" type="xs:string"/>
+    </xs:sequence>
+  </xs:complexType>
+</xs:schema>"#;
+
+    #[test]
+    fn test_imported_schema_string() {
+        let schemas = [("person.xsd", MAIN_SCHEMA), ("address.xsd", ADDRESS_SCHEMA)];
+        let xml = generate_xml_from_strings(&schemas);
+
+        match xml {
+            Ok(result) => {
+                assert!(result.contains("<street>"));
+                assert!(result.contains("<city>"));
+            }
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_namespace_prefixes() {
+        let order = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:po="urn:order"
+           xmlns:addr="urn:address"
+           targetNamespace="urn:order"
+           elementFormDefault="qualified">
+  <xs:import namespace="urn:address" schemaLocation="address.xsd"/>
+  <xs:element name="order">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="id" type="xs:string"/>
+        <xs:element ref="addr:address"/>
+      </xs:sequence>
+      <xs:attribute name="priority" type="xs:int" use="required"/>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+        let address = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           targetNamespace="urn:address"
+           elementFormDefault="qualified">
+  <xs:element name="address">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="street" type="xs:string"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let schemas = [("order.xsd", order), ("address.xsd", address)];
+        let xml = generate_xml_from_strings(&schemas).unwrap();
+
+        assert!(xml.contains("<po:order "));
+        assert!(xml.contains(" xmlns:po=\"urn:order\""));
+        assert!(xml.contains(" xmlns:addr=\"urn:address\""));
+        assert!(xml.contains(" priority=\""));
+        assert!(xml.contains("<po:id>"));
+        assert!(xml.contains("<addr:address>"));
+        assert!(xml.contains("<addr:street>"));
+        assert!(xml.trim_end().ends_with("</po:order>"));
+    }
+
+    #[test]
+    fn test_chameleon_include() {
+        let main = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:m="urn:main"
+           targetNamespace="urn:main"
+           elementFormDefault="qualified">
+  <xs:include schemaLocation="common.xsd"/>
+  <xs:element name="order">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="id" type="xs:string"/>
+        <xs:element name="address" type="m:addressType"/>
+        <xs:element ref="m:note"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+        let common = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- Components without a target namespace -->
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           elementFormDefault="qualified">
+  <xs:complexType name="addressType">
+    <xs:sequence>
+      <xs:element name="street" type="xs:string"/>
+    </xs:sequence>
+  </xs:complexType>
+  <xs:simpleType name="noteType">
+    <xs:restriction base="xs:string">
+      <xs:maxLength value="5"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="note" type="noteType"/>
+</xs:schema>"#;
+
+        let schemas = [("main.xsd", main), ("common.xsd", common)];
+        let xml = generate_xml_from_strings(&schemas).unwrap();
+
+        assert!(xml.contains("<m:order xmlns:m=\"urn:main\">"));
+        assert!(xml.contains("<m:address>"));
+        assert!(xml.contains("<m:street>"));
+        assert!(xml.contains("<m:note>"));
+        assert!(!xml.contains("xmlns:ns"));
+    }
+
+    #[test]
+    fn test_duplicate_type_names() {
+        let order = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:po="urn:order"
+           xmlns:inv="urn:inventory"
+           targetNamespace="urn:order">
+  <xs:import namespace="urn:inventory" schemaLocation="inventory.xsd"/>
+  <xs:complexType name="itemType">
+    <xs:sequence>
+      <xs:element name="quantity" type="xs:int"/>
+    </xs:sequence>
+  </xs:complexType>
+  <xs:element name="order">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="line" type="po:itemType"/>
+        <xs:element name="stock" type="inv:itemType"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+        let inventory = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           targetNamespace="urn:inventory">
+  <xs:complexType name="itemType">
+    <xs:sequence>
+      <xs:element name="sku" type="xs:string"/>
+    </xs:sequence>
+  </xs:complexType>
+</xs:schema>"#;
+
+        let schemas = [("order.xsd", order), ("inventory.xsd", inventory)];
+        match generate_xml_from_strings(&schemas) {
+            Err(XMLGeneratorError::InvalidInputError(error)) => assert_eq!(
+                error,
+                "Type `itemType` is defined in both `urn:inventory` and `urn:order`"
+            ),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_foreign_namespace_types() {
+        let shop = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:shop="urn:shop"
+           xmlns:ext="urn:ext"
+           targetNamespace="urn:shop">
+  <xs:import namespace="urn:ext" schemaLocation="ext.xsd"/>
+  <xs:simpleType name="codeType">
+    <xs:restriction base="xs:string">
+      <xs:pattern value="[A-Z]{3}"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="order">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="local" type="shop:codeType"/>
+        <xs:element name="remote" type="ext:codeType"/>
+        <xs:element name="part" type="ext:partType"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+        let ext = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:ext="urn:ext"
+           targetNamespace="urn:ext">
+  <xs:simpleType name="codeType">
+    <xs:restriction base="ext:digitsType">
+      <xs:maxLength value="5"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="digitsType">
+    <xs:restriction base="xs:string">
+      <xs:pattern value="[0-9]{5}"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:complexType name="partType">
+    <xs:sequence>
+      <xs:element name="serial" type="ext:codeType"/>
+    </xs:sequence>
+  </xs:complexType>
+</xs:schema>"#;
+
+        let schemas = [("shop.xsd", shop), ("ext.xsd", ext)];
+        for _ in 0..10 {
+            let xml = generate_xml_from_strings(&schemas).unwrap();
+            for (tag, digits) in [("local", false), ("remote", true), ("serial", true)] {
+                let open = format!("<{}>", tag);
+                let start = xml.find(&open).unwrap() + open.len();
+                let value = &xml[start..start + xml[start..].find('<').unwrap()];
+                match digits {
+                    true => assert!(
+                        value.len() == 5 && value.chars().all(|x| x.is_ascii_digit()),
+                        "{}",
+                        xml
+                    ),
+                    false => assert!(
+                        value.len() == 3 && value.chars().all(|x| x.is_ascii_uppercase()),
+                        "{}",
+                        xml
+                    ),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_prefixed_type_references() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns="urn:inventory"
+           xmlns:inv="urn:inventory"
+           xmlns:stock="urn:inventory"
+           targetNamespace="urn:inventory">
+  <xs:simpleType name="skuType">
+    <xs:restriction base="xs:string">
+      <xs:pattern value="[A-Z]{2}-[0-9]{4}"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="countType">
+    <xs:restriction base="inv:quantityType">
+      <xs:maxInclusive value="9"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="quantityType">
+    <xs:restriction base="xs:int">
+      <xs:minInclusive value="1"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:complexType name="lineType">
+    <xs:sequence>
+      <xs:element name="sku" type="stock:skuType"/>
+      <xs:element name="count" type="countType"/>
+    </xs:sequence>
+  </xs:complexType>
+  <xs:element name="inventory">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="code" type="inv:skuType"/>
+        <xs:element name="line" type="inv:lineType" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig::default();
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            let skus = get_values(&xml, "sku");
+            assert!(!skus.is_empty(), "{}", xml);
+            for value in get_values(&xml, "code").into_iter().chain(skus) {
+                let (letters, digits) = value.split_once('-').unwrap();
+                assert!(letters.len() == 2 && letters.chars().all(|x| x.is_ascii_uppercase()));
+                assert!(digits.len() == 4 && digits.chars().all(|x| x.is_ascii_digit()));
+            }
+
+            for value in get_values(&xml, "count") {
+                let value = value.parse::<i32>().unwrap();
+                assert!((1..=9).contains(&value), "{}", xml);
+            }
+        }
+    }
+
+    #[test]
+    fn test_missing_import_string() {
+        let schemas = [("person.xsd", MAIN_SCHEMA)];
+        let xml = generate_xml_from_strings(&schemas);
+
+        assert!(xml.is_err());
+    }
+
+    #[test]
+    fn test_strings_do_not_read_files() {
+        let schema = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:include schemaLocation="examples/example.xsd"/>
+</xs:schema>"#;
+
+        assert!(std::path::Path::new("examples/example.xsd").exists());
+
+        let xml = generate_xml_from_strings(&[("main.xsd", schema)]);
+        assert!(xml.is_err());
+    }
+
+    #[test]
+    fn test_leading_bom_and_whitespace() {
+        let xsd = fs::read_to_string("examples/simple.xsd").unwrap();
+
+        for prefix in ["\u{feff}", "\n  ", "\u{feff}\n"] {
+            let schema = format!("{}{}", prefix, xsd);
+            let xml = generate_xml_from_string_with_config(&schema, &GeneratorConfig::default());
+            assert!(xml.unwrap().contains("<person>"), "{:?}", prefix);
+
+            let main = format!("{}{}", prefix, MAIN_SCHEMA);
+            let address = format!("{}{}", prefix, ADDRESS_SCHEMA);
+            let schemas = [
+                ("main.xsd", main.as_str()),
+                ("address.xsd", address.as_str()),
+            ];
+            let xml = generate_xml_from_strings(&schemas).unwrap();
+            assert!(xml.contains("<street>"), "{:?}", prefix);
+        }
+    }
+
+    #[test]
+    fn test_generate_to_file() {
+        let schema = ADDRESS_SCHEMA.replace(
+            "<xs:complexType name=\"addresstype\">",
+            "<xs:element name=\"address\" type=\"addresstype\"/>\n  <xs:complexType name=\"addresstype\">",
+        );
+
+        let filepath = env::temp_dir().join(format!("xmlgenerator-{}.xml", std::process::id()));
+        generate_xml_to_file(&schema, &filepath).unwrap();
+
+        let xml = fs::read_to_string(&filepath).unwrap();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<street>"));
+
+        fs::remove_file(filepath).unwrap();
+    }
+
+    #[test]
+    fn test_generate_bytes() {
+        let schema = ADDRESS_SCHEMA.replace(
+            "<xs:complexType name=\"addresstype\">",
+            "<xs:element name=\"address\" type=\"addresstype\"/>\n  <xs:complexType name=\"addresstype\">",
+        );
+        let config = GeneratorConfig {
+            seed: Some(7),
+            ..Default::default()
+        };
+
+        let bytes = generate_xml_bytes(&schema, &config).unwrap();
+        let xml = String::from_utf8(bytes).unwrap();
+        assert!(xml.starts_with("<?xml version=\"1.1\" encoding=\"UTF-8\"?>"));
+        assert_eq!(
+            xml,
+            generate_xml_from_string_with_config(&schema, &config).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_generate_from_gz() {
+        let schema = fs::read("examples/simple.xsd").unwrap();
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(&schema).unwrap();
+        let bytes = encoder.finish().unwrap();
+
+        let xml = generate_xml_from_gz(&bytes).unwrap();
+        assert!(xml.contains("<person>"));
+        assert!(xml.contains("<name>"));
+        assert!(xml.contains("<age>"));
+
+        match generate_xml_from_gz(&schema) {
+            Err(XMLGeneratorError::IOError(error)) => {
+                assert!(error.starts_with("Failed to decompress schema: "))
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+}
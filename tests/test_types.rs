@@ -0,0 +1,1239 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::get_values;
+    use std::collections::{HashMap, HashSet};
+
+    use xmlgenerator::{
+        EnumerationStrategy, GeneratorConfig, TimezoneMode, assert_well_formed,
+        generate_xml_from_string_with_config,
+    };
+
+    fn schema_for_type(type_name: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="{}" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#,
+            type_name
+        )
+    }
+
+    fn generate_values(type_name: &str, config: &GeneratorConfig) -> Vec<String> {
+        let xsd = schema_for_type(type_name);
+        let xml = generate_xml_from_string_with_config(&xsd, config).unwrap();
+        get_values(&xml, "value")
+    }
+
+    #[test]
+    fn test_float_values() {
+        let config = GeneratorConfig {
+            max_repeats: 10,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            for value in generate_values("xs:float", &config) {
+                assert!(!value.contains(['e', 'E']), "{}", value);
+                assert!(value.parse::<f32>().unwrap().is_finite(), "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_float_special_values() {
+        let config = GeneratorConfig {
+            max_repeats: 50,
+            float_special_values: true,
+            ..Default::default()
+        };
+
+        let mut found_special = false;
+        for _ in 0..10 {
+            for value in generate_values("xs:float", &config) {
+                if ["INF", "-INF", "NaN"].contains(&value.as_str()) {
+                    found_special = true;
+                } else {
+                    assert!(value.parse::<f32>().is_ok(), "{}", value);
+                }
+            }
+        }
+
+        assert!(found_special);
+    }
+
+    #[test]
+    fn test_max_text_length() {
+        let config = GeneratorConfig {
+            max_repeats: 20,
+            max_text_length: Some(5),
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let values = generate_values("xs:string", &config);
+            assert!(!values.is_empty());
+            for value in values {
+                assert!(value.chars().count() <= 5, "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_byte_facets_intersect_natural_range() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="smallByte">
+    <xs:restriction base="xs:byte">
+      <xs:maxInclusive value="50"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="positiveShort">
+    <xs:restriction base="xs:short">
+      <xs:minExclusive value="0"/>
+      <xs:maxInclusive value="100000"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="smallByte" maxOccurs="unbounded"/>
+        <xs:element name="count" type="positiveShort" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 20,
+            ..Default::default()
+        };
+
+        for _ in 0..20 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            for value in get_values(&xml, "value") {
+                let value = value.parse::<i64>().unwrap();
+                assert!((-128..=50).contains(&value), "{}", value);
+            }
+
+            for value in get_values(&xml, "count") {
+                let value = value.parse::<i64>().unwrap();
+                assert!((1..=32767).contains(&value), "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pattern_values_match_exactly() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="code">
+    <xs:restriction base="xs:string">
+      <xs:pattern value="[0-9]{3}"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="choice">
+    <xs:restriction base="xs:string">
+      <xs:pattern value="yes|no"/>
+      <xs:pattern value="[$^]"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="code" maxOccurs="unbounded"/>
+        <xs:element name="answer" type="choice" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 20,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            for value in get_values(&xml, "value") {
+                assert_eq!(value.len(), 3, "{}", value);
+                assert!(value.chars().all(|x| x.is_ascii_digit()), "{}", value);
+            }
+
+            for value in get_values(&xml, "answer") {
+                assert!(
+                    ["yes", "no", "$", "^"].contains(&value.as_str()),
+                    "{}",
+                    value
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_pattern_anchors() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="plain">
+    <xs:restriction base="xs:string">
+      <xs:pattern value="[a-c]{2}"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="anchored">
+    <xs:restriction base="xs:string">
+      <xs:pattern value="^[a-c]{2}$"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="price">
+    <xs:restriction base="xs:string">
+      <xs:pattern value="[0-9]\$"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="plain" maxOccurs="unbounded"/>
+        <xs:element name="code" type="anchored" maxOccurs="unbounded"/>
+        <xs:element name="price" type="price" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 20,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            let mut values = get_values(&xml, "value");
+            values.extend(get_values(&xml, "code"));
+            for value in values {
+                assert_eq!(value.len(), 2, "{}", value);
+                assert!(value.chars().all(|x| ('a'..='c').contains(&x)), "{}", value);
+            }
+
+            for value in get_values(&xml, "price") {
+                assert_eq!(value.len(), 2, "{}", value);
+                assert!(value.ends_with('$'), "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decimal_enumeration_with_pattern() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="rate">
+    <xs:restriction base="xs:decimal">
+      <xs:enumeration value="0.5"/>
+      <xs:enumeration value="1.25"/>
+      <xs:enumeration value="10.00"/>
+      <xs:pattern value="[0-9]+\.[0-9]+"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="rate" maxOccurs="unbounded"/>
+      </xs:sequence>
+      <xs:attribute name="rate" type="rate" use="required"/>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 20,
+            ..Default::default()
+        };
+
+        let allowed = ["0.5", "1.25", "10.00"];
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            let values = get_values(&xml, "value");
+            assert!(!values.is_empty());
+            for value in values {
+                assert!(allowed.contains(&value.as_str()), "{}", value);
+            }
+
+            let rate = xml.split("rate=\"").nth(1).unwrap();
+            let rate = &rate[..rate.find('"').unwrap()];
+            assert!(allowed.contains(&rate), "{}", rate);
+        }
+    }
+
+    #[test]
+    fn test_length_facets() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="code">
+    <xs:restriction base="xs:string">
+      <xs:length value="4"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="label">
+    <xs:restriction base="xs:string">
+      <xs:minLength value="40"/>
+      <xs:maxLength value="50"/>
+      <xs:whiteSpace value="collapse"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="code" maxOccurs="unbounded"/>
+        <xs:element name="label" type="label" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 20,
+            max_text_length: Some(10),
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            for value in get_values(&xml, "value") {
+                assert_eq!(value.chars().count(), 4, "{}", value);
+            }
+
+            let labels = get_values(&xml, "label");
+            assert!(!labels.is_empty());
+            for value in labels {
+                let length = value.chars().count();
+                assert!((40..=50).contains(&length), "{}", value);
+                assert!(!value.contains(['\t', '\n', '\r']), "{}", value);
+                assert!(!value.contains("  "), "{}", value);
+                assert_eq!(value.trim(), value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_integer_exceeds_int_range() {
+        let config = GeneratorConfig {
+            max_repeats: 20,
+            ..Default::default()
+        };
+
+        let mut large = false;
+        for _ in 0..10 {
+            for value in generate_values("xs:integer", &config) {
+                let value = value.parse::<i128>().unwrap();
+                large |= value > i32::MAX as i128;
+            }
+
+            for value in generate_values("xs:int", &config) {
+                assert!(value.parse::<i32>().is_ok(), "{}", value);
+            }
+        }
+        assert!(large);
+
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="bounded">
+    <xs:restriction base="xs:integer">
+      <xs:minInclusive value="-5"/>
+      <xs:maxExclusive value="1000000000000"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="bounded" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            for value in get_values(&xml, "value") {
+                let value = value.parse::<i128>().unwrap();
+                assert!((-5..1_000_000_000_000).contains(&value), "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_digit_facets() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="price">
+    <xs:restriction base="xs:decimal">
+      <xs:totalDigits value="5"/>
+      <xs:fractionDigits value="2"/>
+      <xs:minInclusive value="0"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="count">
+    <xs:restriction base="xs:int">
+      <xs:totalDigits value="2"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="price" maxOccurs="unbounded"/>
+        <xs:element name="count" type="count" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 20,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            for value in get_values(&xml, "value") {
+                let (integer, fraction) = value.split_once('.').unwrap_or((&value, ""));
+                assert!(integer.len() + fraction.len() <= 5, "{}", value);
+                assert!(fraction.len() <= 2, "{}", value);
+                assert!(value.parse::<f64>().unwrap() >= 0.0, "{}", value);
+            }
+
+            for value in get_values(&xml, "count") {
+                let value = value.parse::<i32>().unwrap();
+                assert!((-99..=99).contains(&value), "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decimal_bounds() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="ratio">
+    <xs:restriction base="xs:decimal">
+      <xs:minInclusive value="0.5"/>
+      <xs:maxInclusive value="0.9"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="narrow">
+    <xs:restriction base="xs:decimal">
+      <xs:minExclusive value="0.125"/>
+      <xs:maxExclusive value="0.130"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="count">
+    <xs:restriction base="xs:int">
+      <xs:minInclusive value="0.5"/>
+      <xs:maxExclusive value="3.0"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="ratio" maxOccurs="20"/>
+        <xs:element name="narrow" type="narrow" maxOccurs="20"/>
+        <xs:element name="count" type="count" maxOccurs="20"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            strict: true,
+            ..Default::default()
+        };
+
+        let mut fractional = false;
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            for value in get_values(&xml, "value") {
+                let number = value.parse::<f64>().unwrap();
+                assert!((0.5..=0.9).contains(&number), "{}", value);
+                fractional |= value.contains('.');
+            }
+
+            for value in get_values(&xml, "narrow") {
+                let number = value.parse::<f64>().unwrap();
+                assert!(number > 0.125 && number < 0.13, "{}", value);
+            }
+
+            for value in get_values(&xml, "count") {
+                let value = value.parse::<i32>().unwrap();
+                assert!((1..=2).contains(&value), "{}", value);
+            }
+        }
+        assert!(fractional);
+    }
+
+    #[test]
+    fn test_total_digits_without_fraction_digits() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="amount">
+    <xs:restriction base="xs:decimal">
+      <xs:totalDigits value="4"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="amount" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 20,
+            ..Default::default()
+        };
+
+        let mut integer_lengths = HashSet::new();
+        let mut fraction_lengths = HashSet::new();
+        for _ in 0..20 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            for value in get_values(&xml, "value") {
+                let digits = value.trim_start_matches('-');
+                let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+                let integer = integer.trim_start_matches('0');
+                assert!(integer.len() + fraction.len() <= 4, "{}", value);
+                assert!(value.parse::<f64>().is_ok(), "{}", value);
+                integer_lengths.insert(integer.len());
+                fraction_lengths.insert(fraction.len());
+            }
+        }
+
+        assert!(integer_lengths.contains(&4), "{:?}", integer_lengths);
+        assert!(fraction_lengths.contains(&3), "{:?}", fraction_lengths);
+    }
+
+    #[test]
+    fn test_fraction_digits_option() {
+        let config = GeneratorConfig {
+            max_repeats: 10,
+            fraction_digits: Some(2),
+            ..Default::default()
+        };
+
+        for type_name in ["xs:float", "xs:double"] {
+            for value in generate_values(type_name, &config) {
+                let (_, fraction) = value.split_once('.').unwrap_or((&value, ""));
+                assert!(fraction.len() <= 2, "{}", value);
+                assert!(value.parse::<f64>().unwrap().is_finite(), "{}", value);
+            }
+        }
+
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="precise">
+    <xs:restriction base="xs:double">
+      <xs:fractionDigits value="4"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="precise" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 20,
+            seed: Some(5),
+            ..config
+        };
+        let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+        let fractions: Vec<usize> = get_values(&xml, "value")
+            .iter()
+            .map(|x| x.split_once('.').map_or(0, |(_, fraction)| fraction.len()))
+            .collect();
+        assert!(fractions.iter().all(|x| *x <= 4));
+        assert!(fractions.iter().any(|x| *x > 2));
+    }
+
+    #[test]
+    fn test_binary_length_in_octets() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="key">
+    <xs:restriction base="xs:hexBinary">
+      <xs:length value="4"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="digest">
+    <xs:restriction base="xs:hexBinary">
+      <xs:minLength value="2"/>
+      <xs:maxLength value="3"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="blob">
+    <xs:restriction base="xs:base64Binary">
+      <xs:length value="5"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="key" maxOccurs="unbounded"/>
+        <xs:element name="digest" type="digest" maxOccurs="unbounded"/>
+        <xs:element name="blob" type="blob" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 20,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            for value in get_values(&xml, "value") {
+                assert_eq!(value.len(), 8, "{}", value);
+                assert!(value.chars().all(|x| x.is_ascii_hexdigit()), "{}", value);
+            }
+
+            for value in get_values(&xml, "digest") {
+                assert!([4, 6].contains(&value.len()), "{}", value);
+                assert!(value.chars().all(|x| x.is_ascii_hexdigit()), "{}", value);
+            }
+
+            for value in get_values(&xml, "blob") {
+                assert_eq!(value.len(), 8, "{}", value);
+                assert!(value.ends_with('=') && !value.ends_with("=="), "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hex_binary_case() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="key">
+    <xs:restriction base="xs:hexBinary">
+      <xs:length value="16"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="key" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            seed: Some(7),
+            max_repeats: 10,
+            ..Default::default()
+        };
+
+        let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+        let values = get_values(&xml, "value");
+        assert!(
+            values
+                .iter()
+                .any(|x| x.chars().any(|c| c.is_ascii_alphabetic()))
+        );
+        for value in values.iter() {
+            assert_eq!(value.len(), 32, "{}", value);
+            assert!(!value.chars().any(|x| x.is_ascii_lowercase()), "{}", value);
+        }
+
+        let config = GeneratorConfig {
+            lowercase_hex: true,
+            ..config
+        };
+
+        let lowercase = generate_xml_from_string_with_config(xsd, &config).unwrap();
+        let expected: Vec<String> = values.iter().map(|x| x.to_lowercase()).collect();
+        assert_eq!(get_values(&lowercase, "value"), expected);
+    }
+
+    #[test]
+    fn test_decimal_zero_and_canonical_form() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="small">
+    <xs:restriction base="xs:decimal">
+      <xs:minInclusive value="-1"/>
+      <xs:maxInclusive value="1"/>
+      <xs:fractionDigits value="1"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="small" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        for canonical_decimals in [false, true] {
+            let config = GeneratorConfig {
+                max_repeats: 50,
+                canonical_decimals,
+                ..Default::default()
+            };
+
+            for _ in 0..10 {
+                let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+                for value in get_values(&xml, "value") {
+                    let number = value.parse::<f64>().unwrap();
+                    assert!((-1.0..=1.0).contains(&number), "{}", value);
+                    assert!(!value.starts_with("-0"), "{}", value);
+                    if number == 0.0 {
+                        assert_eq!(value, "0");
+                    }
+
+                    if canonical_decimals {
+                        assert!(!value.ends_with('.'), "{}", value);
+                        assert!(!value.contains('.') || !value.ends_with('0'), "{}", value);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixed_value_scoped_to_declaration() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:complexType name="a">
+    <xs:sequence>
+      <xs:element name="code" type="xs:string" fixed="X"/>
+    </xs:sequence>
+  </xs:complexType>
+  <xs:complexType name="b">
+    <xs:sequence>
+      <xs:element name="code" type="xs:int"/>
+    </xs:sequence>
+  </xs:complexType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="first" type="a"/>
+        <xs:element name="second" type="b"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &GeneratorConfig::default());
+            let xml = xml.unwrap();
+            let codes = get_values(&xml, "code");
+            assert_eq!(codes.len(), 2, "{}", xml);
+            assert_eq!(codes[0], "X");
+            assert!(codes[1].parse::<i32>().is_ok(), "{}", xml);
+        }
+    }
+
+    #[test]
+    fn test_same_name_in_different_types() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:complexType name="a">
+    <xs:sequence>
+      <xs:element name="value" type="xs:int"/>
+    </xs:sequence>
+    <xs:attribute name="code" type="xs:int" use="required"/>
+  </xs:complexType>
+  <xs:complexType name="b">
+    <xs:sequence>
+      <xs:element name="value" type="xs:language"/>
+    </xs:sequence>
+    <xs:attribute name="code" type="xs:language" use="required"/>
+  </xs:complexType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="first" type="a"/>
+        <xs:element name="second" type="b"/>
+        <xs:element name="third">
+          <xs:complexType>
+            <xs:sequence>
+              <xs:element name="value">
+                <xs:simpleType>
+                  <xs:restriction base="xs:string">
+                    <xs:enumeration value="inline"/>
+                  </xs:restriction>
+                </xs:simpleType>
+              </xs:element>
+            </xs:sequence>
+          </xs:complexType>
+        </xs:element>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            strict: true,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            let values = get_values(&xml, "value");
+            assert_eq!(values.len(), 3, "{}", xml);
+            assert!(values[0].parse::<i32>().is_ok(), "{}", xml);
+            assert!(values[1].parse::<i32>().is_err(), "{}", xml);
+            assert!(
+                values[1]
+                    .chars()
+                    .all(|x| x.is_ascii_alphabetic() || x == '-')
+            );
+            assert_eq!(values[2], "inline");
+
+            let first = &xml[xml.find("<first").unwrap()..];
+            let code = first.split('"').nth(1).unwrap();
+            assert!(code.parse::<i32>().is_ok(), "{}", xml);
+
+            let second = &xml[xml.find("<second").unwrap()..];
+            let code = second.split('"').nth(1).unwrap();
+            assert!(code.parse::<i32>().is_err(), "{}", xml);
+        }
+    }
+
+    #[test]
+    fn test_fixed_values() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="versionType">
+    <xs:restriction base="xs:decimal">
+      <xs:enumeration value="1.0"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="version" type="versionType" maxOccurs="unbounded"/>
+        <xs:element name="value" type="xs:int" fixed="42" maxOccurs="unbounded"/>
+      </xs:sequence>
+      <xs:attribute name="unit" type="xs:string" fixed="kg" use="required"/>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 10,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            assert!(xml.contains("<values unit=\"kg\">"), "{}", xml);
+
+            let versions = get_values(&xml, "version");
+            assert!(!versions.is_empty());
+            assert!(versions.iter().all(|x| x == "1.0"), "{}", xml);
+
+            let values = get_values(&xml, "value");
+            assert!(!values.is_empty());
+            assert!(values.iter().all(|x| x == "42"), "{}", xml);
+        }
+    }
+
+    fn get_attribute<'a>(xml: &'a str, name: &str) -> &'a str {
+        let start = xml.find(&format!(" {}=\"", name)).unwrap() + name.len() + 3;
+        let end = start + xml[start..].find('"').unwrap();
+        &xml[start..end]
+    }
+
+    #[test]
+    fn test_attribute_patterns() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="codeType">
+    <xs:restriction base="xs:string">
+      <xs:pattern value="[A-Z]{2}-[0-9]{4}"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:attribute name="lang">
+    <xs:simpleType>
+      <xs:restriction base="xs:string">
+        <xs:pattern value="[a-z]{2}_[A-Z]{2}"/>
+      </xs:restriction>
+    </xs:simpleType>
+  </xs:attribute>
+  <xs:element name="item">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="name" type="xs:string"/>
+      </xs:sequence>
+      <xs:attribute ref="lang" use="required"/>
+      <xs:attribute name="code" type="codeType" use="required"/>
+      <xs:attribute name="ref">
+        <xs:simpleType>
+          <xs:restriction base="xs:string">
+            <xs:pattern value="r[0-9]{3}"/>
+          </xs:restriction>
+        </xs:simpleType>
+      </xs:attribute>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        for _ in 0..10 {
+            let xml =
+                generate_xml_from_string_with_config(xsd, &GeneratorConfig::default()).unwrap();
+
+            let code = get_attribute(&xml, "code").as_bytes();
+            assert_eq!(code.len(), 7, "{}", xml);
+            assert!(code[..2].iter().all(|x| x.is_ascii_uppercase()), "{}", xml);
+            assert_eq!(code[2], b'-', "{}", xml);
+            assert!(code[3..].iter().all(|x| x.is_ascii_digit()), "{}", xml);
+
+            let lang = get_attribute(&xml, "lang").as_bytes();
+            assert_eq!(lang.len(), 5, "{}", xml);
+            assert!(lang[..2].iter().all(|x| x.is_ascii_lowercase()), "{}", xml);
+            assert_eq!(lang[2], b'_', "{}", xml);
+            assert!(lang[3..].iter().all(|x| x.is_ascii_uppercase()), "{}", xml);
+
+            if xml.contains(" ref=\"") {
+                let reference = get_attribute(&xml, "ref");
+                assert_eq!(reference.len(), 4, "{}", xml);
+                assert!(reference.starts_with('r'), "{}", xml);
+                assert!(
+                    reference[1..].chars().all(|x| x.is_ascii_digit()),
+                    "{}",
+                    xml
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_escaped_enumeration_values() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="company">
+    <xs:restriction base="xs:string">
+      <xs:enumeration value="A &amp; B"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="comparison">
+    <xs:restriction base="xs:string">
+      <xs:enumeration value="&lt;="/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="company"/>
+        <xs:element name="operator" type="comparison"/>
+      </xs:sequence>
+      <xs:attribute name="owner" type="company" use="required"/>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let xml = generate_xml_from_string_with_config(xsd, &GeneratorConfig::default()).unwrap();
+        assert_well_formed(&xml);
+        assert_eq!(get_values(&xml, "value"), vec!["A &amp; B"]);
+        assert_eq!(get_values(&xml, "operator"), vec!["&lt;="]);
+        assert_eq!(get_attribute(&xml, "owner"), "A &amp; B");
+    }
+
+    #[test]
+    fn test_weighted_first_enumerations() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="status">
+    <xs:restriction base="xs:string">
+      <xs:enumeration value="active"/>
+      <xs:enumeration value="pending"/>
+      <xs:enumeration value="suspended"/>
+      <xs:enumeration value="closed"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="value" type="status" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            enumeration_strategy: EnumerationStrategy::WeightedFirst,
+            max_repeats: 20,
+            ..Default::default()
+        };
+
+        let mut counts = HashMap::new();
+        for _ in 0..100 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            for value in get_values(&xml, "value") {
+                *counts.entry(value).or_insert(0) += 1;
+            }
+        }
+
+        let total: usize = counts.values().sum();
+        let first = counts["active"];
+        let last = counts["closed"];
+        assert!(first * 3 > total, "{:?}", counts);
+        assert!(first > 2 * last, "{:?}", counts);
+    }
+
+    fn is_language(value: &str) -> bool {
+        let mut parts = value.split('-');
+        let primary = parts.next().unwrap();
+        if primary.is_empty() || primary.len() > 8 {
+            return false;
+        }
+
+        if !primary.chars().all(|x| x.is_ascii_alphabetic()) {
+            return false;
+        }
+
+        parts.all(|x| !x.is_empty() && x.len() <= 8 && x.chars().all(|x| x.is_ascii_alphanumeric()))
+    }
+
+    #[test]
+    fn test_language_values() {
+        let config = GeneratorConfig {
+            max_repeats: 10,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            for value in generate_values("xs:language", &config) {
+                assert!(is_language(&value), "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_simple_content_restriction() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:complexType name="measure">
+    <xs:simpleContent>
+      <xs:extension base="xs:int">
+        <xs:attribute name="unit" type="xs:string" use="required"/>
+      </xs:extension>
+    </xs:simpleContent>
+  </xs:complexType>
+  <xs:complexType name="percentage">
+    <xs:simpleContent>
+      <xs:restriction base="measure">
+        <xs:minInclusive value="0"/>
+        <xs:maxInclusive value="100"/>
+      </xs:restriction>
+    </xs:simpleContent>
+  </xs:complexType>
+  <xs:element name="report">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="score" type="percentage" maxOccurs="unbounded"/>
+        <xs:element name="length" type="measure"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 20,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            let scores: Vec<&str> = xml.split("<score unit=\"").skip(1).collect();
+            assert!(!scores.is_empty());
+            for part in scores {
+                let start = part.find('>').unwrap() + 1;
+                let end = part.find("</score>").unwrap();
+                let value = part[start..end].parse::<i32>().unwrap();
+                assert!((0..=100).contains(&value), "{}", value);
+            }
+        }
+    }
+
+    fn is_timezone(value: &str) -> bool {
+        if value == "Z" {
+            return true;
+        }
+
+        let bytes = value.as_bytes();
+        if bytes.len() != 6 || !matches!(bytes[0], b'+' | b'-') || bytes[3] != b':' {
+            return false;
+        }
+
+        let hours = value[1..3].parse::<u32>();
+        let minutes = value[4..6].parse::<u32>();
+        match (hours, minutes) {
+            (Ok(hours), Ok(minutes)) => hours <= 14 && minutes < 60,
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_date_time_stamp_values() {
+        let config = GeneratorConfig {
+            max_repeats: 10,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let values = generate_values("xs:dateTimeStamp", &config);
+            assert!(!values.is_empty());
+            for value in values {
+                assert!(value.len() > 19, "{}", value);
+                assert_eq!(&value[10..11], "T", "{}", value);
+                assert!(is_timezone(&value[19..]), "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_explicit_timezone_facet() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="zonedDate">
+    <xs:restriction base="xs:date">
+      <xs:explicitTimezone value="required"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="localTime">
+    <xs:restriction base="xs:dateTime">
+      <xs:explicitTimezone value="prohibited"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="date" type="zonedDate" maxOccurs="unbounded"/>
+        <xs:element name="stamp" type="localTime" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 10,
+            timezone: TimezoneMode::Random,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            for value in get_values(&xml, "date") {
+                assert!(is_timezone(&value[10..]), "{}", value);
+            }
+            for value in get_values(&xml, "stamp") {
+                assert_eq!(value.len(), 19, "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_timezone_modes() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="date" type="xs:date" maxOccurs="unbounded"/>
+        <xs:element name="time" type="xs:time" maxOccurs="unbounded"/>
+        <xs:element name="stamp" type="xs:dateTime" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        for (timezone, has_offset) in [(TimezoneMode::Never, false), (TimezoneMode::Always, true)] {
+            let config = GeneratorConfig {
+                max_repeats: 10,
+                timezone,
+                ..Default::default()
+            };
+
+            for _ in 0..10 {
+                let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+                for (tag, prefix) in [("date", 10), ("time", 8), ("stamp", 19)] {
+                    let values = get_values(&xml, tag);
+                    assert!(!values.is_empty());
+                    for value in values {
+                        let offset = &value[prefix..];
+                        assert_eq!(!offset.is_empty(), has_offset, "{}", value);
+                        assert!(offset.is_empty() || is_timezone(offset), "{}", value);
+                    }
+                }
+            }
+        }
+    }
+
+    fn get_seconds(value: &str) -> u32 {
+        let parts: Vec<u32> = value.split(':').map(|x| x.parse().unwrap()).collect();
+        assert_eq!(parts.len(), 3, "{}", value);
+        (parts[0] * 60 + parts[1]) * 60 + parts[2]
+    }
+
+    #[test]
+    fn test_time_bounds() {
+        let xsd = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="officeHours">
+    <xs:restriction base="xs:time">
+      <xs:minInclusive value="09:00:00"/>
+      <xs:maxInclusive value="17:00:00"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="lunchBreak">
+    <xs:restriction base="xs:time">
+      <xs:minExclusive value="12:00:00Z"/>
+      <xs:maxExclusive value="12:30:00Z"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="values">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="open" type="officeHours" maxOccurs="unbounded"/>
+        <xs:element name="lunch" type="lunchBreak" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+        let config = GeneratorConfig {
+            max_repeats: 10,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let xml = generate_xml_from_string_with_config(xsd, &config).unwrap();
+            assert!(xml.contains("<open>") && xml.contains("<lunch>"), "{}", xml);
+            for value in get_values(&xml, "open") {
+                let seconds = get_seconds(&value);
+                assert!((9 * 3600..=17 * 3600).contains(&seconds), "{}", value);
+            }
+
+            for value in get_values(&xml, "lunch") {
+                let time = value.strip_suffix('Z').unwrap();
+                let seconds = get_seconds(time);
+                assert!(
+                    seconds > 12 * 3600 && seconds < 12 * 3600 + 1800,
+                    "{}",
+                    value
+                );
+            }
+        }
+    }
+}